@@ -0,0 +1,87 @@
+//
+// ned, https://github.com/nevdelap/ned, backup.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use crate::ned_error::StringError;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Backup {
+    /// file.txt is backed up to file.txt~, or whatever suffix was given.
+    Suffix(String),
+    /// file.txt is backed up to file.txt.~1~, file.txt.~2~, etc. like GNU cp --backup=numbered.
+    Numbered,
+}
+
+impl FromStr for Backup {
+    type Err = StringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Ok(Backup::Suffix("~".to_string())),
+            "numbered" => Ok(Backup::Numbered),
+            _ if s.contains('/') || s.contains(std::path::MAIN_SEPARATOR) => Err(StringError {
+                err: format!("invalid backup suffix {}", s),
+            }),
+            _ => Ok(Backup::Suffix(s.to_string())),
+        }
+    }
+}
+
+impl Backup {
+    /// Copy the file to its backup, returning the path of the backup.
+    pub fn back_up(&self, path: &Path) -> io::Result<PathBuf> {
+        let backup_path = self.backup_path(path)?;
+        fs::copy(path, &backup_path)?;
+        Ok(backup_path)
+    }
+
+    fn backup_path(&self, path: &Path) -> io::Result<PathBuf> {
+        let file_name = path
+            .file_name()
+            .expect("Bug, a file being replaced has a file name.")
+            .to_string_lossy()
+            .to_string();
+        match self {
+            Backup::Suffix(suffix) => Ok(path.with_file_name(format!("{}{}", file_name, suffix))),
+            Backup::Numbered => {
+                let dir = match path.parent() {
+                    Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                    _ => PathBuf::from("."),
+                };
+                let prefix = format!("{}.~", file_name);
+                let mut last_number = 0;
+                for entry in fs::read_dir(dir)? {
+                    let entry_name = entry?.file_name().to_string_lossy().to_string();
+                    let number = entry_name
+                        .strip_prefix(&prefix)
+                        .and_then(|rest| rest.strip_suffix('~'))
+                        .and_then(|number| number.parse::<usize>().ok());
+                    if let Some(number) = number {
+                        last_number = std::cmp::max(last_number, number);
+                    }
+                }
+                Ok(path.with_file_name(format!("{}{}~", prefix, last_number + 1)))
+            }
+        }
+    }
+}
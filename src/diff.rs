@@ -0,0 +1,279 @@
+//
+// ned, https://github.com/nevdelap/ned, diff.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use crate::ned_error::NedResult;
+use ansi_term::Colour::{Cyan, Green, Red};
+use ansi_term::Style;
+use std::io::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Edit {
+    /// Indices of the same line in the old and new texts.
+    Equal(usize, usize),
+    /// Index of a line only in the old text.
+    Delete(usize),
+    /// Index of a line only in the new text.
+    Insert(usize),
+}
+
+/// Write a unified diff of the old and new texts, with context lines of context around each
+/// change. Nothing is written if the texts are the same.
+pub fn write_diff(
+    output: &mut dyn Write,
+    colors: bool,
+    old_name: &str,
+    new_name: &str,
+    old: &str,
+    new: &str,
+    context: usize,
+) -> NedResult<()> {
+    let old_lines = old.split_inclusive('\n').collect::<Vec<&str>>();
+    let new_lines = new.split_inclusive('\n').collect::<Vec<&str>>();
+    let edits = diff_lines(&old_lines, &new_lines);
+    let hunks = make_hunks(&edits, context);
+    if hunks.is_empty() {
+        return Ok(());
+    }
+
+    let header = format!("--- {}\n+++ {}\n", old_name, new_name);
+    write_colored(output, colors, Style::new().bold(), &header)?;
+    for (start, end) in hunks {
+        let (old_start, old_count) = hunk_range(&edits, start, end, true);
+        let (new_start, new_count) = hunk_range(&edits, start, end, false);
+        let hunk_header = format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start, old_count, new_start, new_count
+        );
+        write_colored(output, colors, Cyan.normal(), &hunk_header)?;
+        for edit in &edits[start..end] {
+            match *edit {
+                Edit::Equal(old_index, _) => {
+                    write_line(output, colors, Style::new(), ' ', old_lines[old_index])?
+                }
+                Edit::Delete(old_index) => {
+                    write_line(output, colors, Red.normal(), '-', old_lines[old_index])?
+                }
+                Edit::Insert(new_index) => {
+                    write_line(output, colors, Green.normal(), '+', new_lines[new_index])?
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A group of lines next to each other that are different in the new text.
+pub struct Change<'a> {
+    /// The 1 based number of the first line in the old text, or of the line before the
+    /// change, if there are no old lines.
+    pub line_number: usize,
+    pub old_lines: Vec<&'a str>,
+    pub new_lines: Vec<&'a str>,
+}
+
+/// The groups of lines that are different in the new text, with their line endings.
+pub fn changes<'a>(old: &'a str, new: &'a str) -> Vec<Change<'a>> {
+    let old_lines = old.split_inclusive('\n').collect::<Vec<&str>>();
+    let new_lines = new.split_inclusive('\n').collect::<Vec<&str>>();
+    let edits = diff_lines(&old_lines, &new_lines);
+    make_hunks(&edits, 0)
+        .into_iter()
+        .map(|(start, end)| {
+            let mut change = Change {
+                line_number: hunk_range(&edits, start, end, true).0,
+                old_lines: vec![],
+                new_lines: vec![],
+            };
+            for edit in &edits[start..end] {
+                match *edit {
+                    Edit::Delete(old_index) => change.old_lines.push(old_lines[old_index]),
+                    Edit::Insert(new_index) => change.new_lines.push(new_lines[new_index]),
+                    Edit::Equal(_, _) => {}
+                }
+            }
+            change
+        })
+        .collect()
+}
+
+fn write_line(
+    output: &mut dyn Write,
+    colors: bool,
+    style: Style,
+    prefix: char,
+    line: &str,
+) -> NedResult<()> {
+    let text = line.strip_suffix('\n').unwrap_or(line);
+    write_colored(output, colors, style, &format!("{}{}\n", prefix, text))?;
+    if !line.ends_with('\n') {
+        output.write_all(b"\\ No newline at end of file\n")?;
+    }
+    Ok(())
+}
+
+fn write_colored(output: &mut dyn Write, colors: bool, style: Style, text: &str) -> NedResult<()> {
+    if colors {
+        // Color up to the newline so that the color doesn't run on into the next line.
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        output.write_all(&format!("{}\n", style.paint(text)).into_bytes())?;
+    } else {
+        output.write_all(text.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Return the 1 based start line number and the line count of the old or the new side of a
+/// hunk. An empty side starts at the number of the line before it.
+fn hunk_range(edits: &[Edit], start: usize, end: usize, old: bool) -> (usize, usize) {
+    let is_side = |edit: &&Edit| match **edit {
+        Edit::Equal(_, _) => true,
+        Edit::Delete(_) => old,
+        Edit::Insert(_) => !old,
+    };
+    let before = edits[..start].iter().filter(is_side).count();
+    let count = edits[start..end].iter().filter(is_side).count();
+    (if count == 0 { before } else { before + 1 }, count)
+}
+
+/// Group the edits into hunks of changes with up to context unchanged lines around them,
+/// returning the start and end indices of each hunk in the edits.
+fn make_hunks(edits: &[Edit], context: usize) -> Vec<(usize, usize)> {
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for (index, edit) in edits.iter().enumerate() {
+        if let Edit::Equal(_, _) = edit {
+            continue;
+        }
+        let start = index.saturating_sub(context);
+        let end = std::cmp::min(edits.len(), index + context + 1);
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+    hunks
+}
+
+/// Find the shortest edit script turning the old lines into the new lines, using Myers'
+/// algorithm, after taking off the lines the two have in common at their start and end.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    let mut edits = (0..prefix)
+        .map(|index| Edit::Equal(index, index))
+        .collect::<Vec<Edit>>();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+    edits.extend(
+        myers(old_middle, new_middle)
+            .into_iter()
+            .map(|edit| match edit {
+                Edit::Equal(old_index, new_index) => {
+                    Edit::Equal(old_index + prefix, new_index + prefix)
+                }
+                Edit::Delete(old_index) => Edit::Delete(old_index + prefix),
+                Edit::Insert(new_index) => Edit::Insert(new_index + prefix),
+            }),
+    );
+    edits.extend(
+        (0..suffix)
+            .map(|index| Edit::Equal(old.len() - suffix + index, new.len() - suffix + index)),
+    );
+    edits
+}
+
+fn myers(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let n = old.len() as isize;
+    let m = new.len() as isize;
+    let max = n + m;
+    let offset = max + 1;
+    let index = |k: isize| (k + offset) as usize;
+    // The furthest reaching x on each diagonal k.
+    let mut v = vec![0isize; 2 * offset as usize + 1];
+    // The furthest reaching x on diagonals -d..=d before each round d, which is all that is
+    // needed to walk back along the path.
+    let mut trace = vec![];
+    'search: for d in 0..=max {
+        trace.push(v[index(-d)..=index(d)].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                v[index(k + 1)]
+            } else {
+                v[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // Walk back through the rounds to recover the path.
+    let mut edits = vec![];
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        if d == 0 {
+            while x > 0 && y > 0 {
+                x -= 1;
+                y -= 1;
+                edits.push(Edit::Equal(x as usize, y as usize));
+            }
+            break;
+        }
+        let k = x - y;
+        let at = |k: isize| v[(k + d) as usize];
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = at(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Equal(x as usize, y as usize));
+        }
+        if x == prev_x {
+            edits.push(Edit::Insert(prev_y as usize));
+        } else {
+            edits.push(Edit::Delete(prev_x as usize));
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    edits.reverse();
+    edits
+}
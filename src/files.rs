@@ -21,23 +21,35 @@
 use crate::ned_error::stderr_write_err;
 use crate::parameters::Parameters;
 use std::iter::IntoIterator;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use walkdir::{IntoIter, WalkDir};
 
 pub struct Files {
+    /// The number of errors walking the directories, which have been reported.
+    pub errors: usize,
+    /// The number of files skipped because of --include or --exclude.
+    pub excluded: usize,
+    /// The number of hidden files skipped.
+    pub hidden: usize,
+    /// The number of files found, whether they were skipped or not.
+    pub walked: usize,
     parameters: Parameters,
     walkdir: Box<IntoIter>,
 }
 
 impl Files {
-    pub fn new(parameters: &Parameters, glob: &str) -> Files {
-        let mut walkdir = WalkDir::new(&glob)
+    pub fn new(parameters: &Parameters, glob: &Path) -> Files {
+        let mut walkdir = WalkDir::new(glob)
             .follow_links(parameters.follow)
             .sort_by(|a, b| a.file_name().cmp(b.file_name()));
         if !parameters.recursive {
             walkdir = walkdir.max_depth(1);
         }
         Files {
+            errors: 0,
+            excluded: 0,
+            hidden: 0,
+            walked: 0,
             parameters: parameters.clone(),
             walkdir: Box::new(walkdir.into_iter()),
         }
@@ -53,42 +65,52 @@ impl Iterator for Files {
                 Some(entry) => match entry {
                     Ok(entry) => {
                         if let Some(file_name) = entry.path().file_name() {
-                            if let Some(file_name) = file_name.to_str() {
-                                let file_type = entry.file_type();
-                                if file_type.is_dir() {
-                                    let excluded_dir = self
-                                        .parameters
-                                        .exclude_dirs
-                                        .iter()
-                                        .any(|pattern| pattern.matches(file_name));
-                                    if excluded_dir {
-                                        self.walkdir.skip_current_dir();
-                                    }
-                                    continue;
+                            // Names that aren't UTF-8 are matched with their bad bytes replaced.
+                            let file_name = file_name.to_string_lossy();
+                            let file_type = entry.file_type();
+                            if file_type.is_dir() {
+                                let excluded_dir = self
+                                    .parameters
+                                    .exclude_dirs
+                                    .iter()
+                                    .any(|pattern| pattern.matches(&file_name));
+                                if excluded_dir {
+                                    self.walkdir.skip_current_dir();
                                 }
-                                let included_file = file_type.is_file()
-                                    && (self.parameters.includes.is_empty()
-                                        || self
-                                            .parameters
-                                            .includes
-                                            .iter()
-                                            .any(|pattern| pattern.matches(file_name)));
-                                let excluded_file = file_type.is_file()
-                                    && self
+                                continue;
+                            }
+                            let included_file = file_type.is_file()
+                                && (self.parameters.includes.is_empty()
+                                    || self
                                         .parameters
-                                        .excludes
+                                        .includes
                                         .iter()
-                                        .any(|pattern| pattern.matches(file_name));
-                                let all = self.parameters.all;
-                                let hidden = file_name.starts_with('.');
-                                if included_file && !excluded_file && (all || !hidden) {
-                                    return Some(Box::new(entry.path().to_path_buf()));
+                                        .any(|pattern| pattern.matches(&file_name)));
+                            let excluded_file = file_type.is_file()
+                                && self
+                                    .parameters
+                                    .excludes
+                                    .iter()
+                                    .any(|pattern| pattern.matches(&file_name));
+                            let all = self.parameters.all;
+                            let hidden = file_name.starts_with('.');
+                            if file_type.is_file() {
+                                self.walked += 1;
+                            }
+                            if included_file && !excluded_file && (all || !hidden) {
+                                return Some(Box::new(entry.path().to_path_buf()));
+                            } else if file_type.is_file() {
+                                if !included_file || excluded_file {
+                                    self.excluded += 1;
+                                } else {
+                                    self.hidden += 1;
                                 }
                             }
                         }
                     }
                     Err(err) => {
                         stderr_write_err(&err);
+                        self.errors += 1;
                         continue;
                     }
                 },
@@ -0,0 +1,108 @@
+//
+// ned, https://github.com/nevdelap/ned, git.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Which files have uncommitted changes, staged or unstaged, in the git work trees that files
+/// being replaced are in, found by asking git. Each work tree is asked about once.
+#[derive(Default)]
+pub struct GitStatus {
+    /// The work tree that each directory is in, or None if it isn't in one.
+    work_trees: HashMap<PathBuf, Option<PathBuf>>,
+    /// The canonical paths of the files with uncommitted changes in each work tree.
+    changed_files: HashMap<PathBuf, HashSet<PathBuf>>,
+}
+
+impl GitStatus {
+    /// Whether the file is in a git work tree and has uncommitted changes. Files that aren't
+    /// in a work tree, or when git can't be run, don't.
+    pub fn has_uncommitted_changes(&mut self, path: &Path) -> bool {
+        let path = match fs::canonicalize(path) {
+            Ok(path) => path,
+            Err(_) => return false,
+        };
+        let dir = match path.parent() {
+            Some(dir) => dir.to_path_buf(),
+            None => return false,
+        };
+        let work_tree = self
+            .work_trees
+            .entry(dir.clone())
+            .or_insert_with(|| work_tree(&dir))
+            .clone();
+        match work_tree {
+            Some(work_tree) => self
+                .changed_files
+                .entry(work_tree.clone())
+                .or_insert_with(|| changed_files(&work_tree))
+                .contains(&path),
+            None => false,
+        }
+    }
+}
+
+fn work_tree(dir: &Path) -> Option<PathBuf> {
+    let output = git(dir, &["rev-parse", "--show-toplevel"])?;
+    let work_tree = String::from_utf8_lossy(&output);
+    fs::canonicalize(work_tree.trim_end_matches(&['\r', '\n'][..])).ok()
+}
+
+fn changed_files(work_tree: &Path) -> HashSet<PathBuf> {
+    let mut changed_files = HashSet::new();
+    if let Some(output) = git(
+        work_tree,
+        &["status", "--porcelain=v1", "-z", "--untracked-files=no"],
+    ) {
+        // Each entry is XY PATH, where X and Y are the staged and unstaged status, and for
+        // renames and copies it is followed by an entry with the original path.
+        let mut entries = output.split(|byte| *byte == 0);
+        while let Some(entry) = entries.next() {
+            if entry.len() < 4 {
+                continue;
+            }
+            let path = String::from_utf8_lossy(&entry[3..]);
+            changed_files.insert(work_tree.join(path.as_ref()));
+            if entry[0] == b'R' || entry[0] == b'C' {
+                entries.next();
+            }
+        }
+    }
+    changed_files
+}
+
+/// Run git in dir, returning its output if it succeeded.
+fn git(dir: &Path, args: &[&str]) -> Option<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if output.status.success() {
+        Some(output.stdout)
+    } else {
+        None
+    }
+}
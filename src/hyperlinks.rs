@@ -0,0 +1,79 @@
+//
+// ned, https://github.com/nevdelap/ned, hyperlinks.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, MAIN_SEPARATOR};
+
+/// The URL template used when --hyperlinks is given without one.
+pub static DEFAULT_TEMPLATE: &str = "file:///{path}";
+
+/// The absolute path of a file, encoded for a URL, to replace {path} with in --hyperlinks
+/// URLs, or None if the file doesn't exist.
+pub fn hyperlink_path(file_name: &str) -> Option<String> {
+    fs::canonicalize(file_name).ok().map(|path| url_path(&path))
+}
+
+/// Make a URL from a --hyperlinks template, replacing {path} with the path from
+/// hyperlink_path(), and {line} and {column} with the line number and column, or 1 if there
+/// isn't one.
+pub fn url(
+    template: &str,
+    path: &str,
+    line_number: Option<usize>,
+    column: Option<usize>,
+) -> String {
+    template
+        .replace("{path}", path)
+        .replace("{line}", &line_number.unwrap_or(1).to_string())
+        .replace("{column}", &column.unwrap_or(1).to_string())
+}
+
+/// Wrap text in an OSC 8 hyperlink to the URL, which terminals that support them make
+/// clickable, and others don't show.
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// An absolute path as the path of a file:/// URL, without its leading /, with / as its
+/// separator, and without the \\?\ prefix that Windows gives canonical paths, percent encoded.
+pub fn url_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    let path = path.strip_prefix(r"\\?\").unwrap_or(&path);
+    // Colons, as after a drive letter, are allowed after file:///, unlike at the start of a
+    // relative URL.
+    encode_path(path.replace(MAIN_SEPARATOR, "/").trim_start_matches('/')).replace("%3A", ":")
+}
+
+/// Percent encode the bytes of a path that aren't allowed in a URL path.
+pub fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            byte => {
+                let _ = write!(encoded, "%{:02X}", byte);
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,222 @@
+//
+// ned, https://github.com/nevdelap/ned, journal.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use crate::ned_error::{stderr_write_file_err, NedError, NedResult, StringError};
+use crate::preserve::Preserve;
+use crate::rewrite::write_file_atomically;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use time::OffsetDateTime;
+
+/// The journal of a replace run, which records the original content of each file it
+/// replaces so that the run can be undone with --undo.
+///
+/// Each run has a directory in the journal directory named by its run id, which sorts by
+/// the time the run started, and is shown when the run finishes. For each file replaced it has a .orig file with the file's
+/// original content, and a .info file with a hash of the content that replaced it followed
+/// by the file's path. Both are named by a hash of the file's path.
+#[derive(Clone, Debug)]
+pub struct Journal {
+    pub dir: PathBuf,
+    pub run: String,
+}
+
+impl Journal {
+    /// A journal for a new run in the given directory, or in the default directory.
+    pub fn new(dir: Option<String>) -> NedResult<Journal> {
+        let dir = match dir {
+            Some(dir) => PathBuf::from(dir),
+            None => default_journal_dir().ok_or_else(|| {
+                NedError::from(
+                    "can't find a directory for the journal, give one with --journal=DIR"
+                        .to_string(),
+                )
+            })?,
+        };
+        let run = run_id(OffsetDateTime::now_utc(), process::id());
+        Ok(Journal { dir, run })
+    }
+
+    /// Whether any files have been recorded as replaced in this run.
+    pub fn recorded_any(&self) -> bool {
+        fs::read_dir(self.dir.join(&self.run))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .any(|entry| entry.path().extension() == Some(OsStr::new("info")))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Keep the original content of the file at path, which must be canonical, before it is
+    /// replaced. If the same file is replaced more than once in the run the content it had
+    /// before the first replace is kept.
+    pub fn keep_original(&self, path: &Path) -> NedResult<()> {
+        let entry = self.entry(path);
+        fs::create_dir_all(self.dir.join(&self.run))?;
+        let orig_path = entry.with_extension("orig");
+        if !orig_path.exists() {
+            fs::copy(path, &orig_path)?;
+        }
+        Ok(())
+    }
+
+    /// Record that the file at path has been replaced by new_content, which makes its kept
+    /// original content restorable with --undo.
+    pub fn record(&self, path: &Path, new_content: &[u8]) -> NedResult<()> {
+        let info = format!("{:016x}\n{}", hash(new_content), path.to_string_lossy());
+        write_file_atomically(
+            &self.entry(path).with_extension("info"),
+            info.as_bytes(),
+            &Preserve::default(),
+        )?;
+        Ok(())
+    }
+
+    /// Forget the original content of the file at path when replacing it failed, unless it
+    /// was replaced earlier in the run.
+    pub fn forget_original(&self, path: &Path) {
+        let entry = self.entry(path);
+        if !entry.with_extension("info").exists() {
+            let _ = fs::remove_file(entry.with_extension("orig"));
+        }
+    }
+
+    /// The path, without an extension, of the entries for the file at path.
+    fn entry(&self, path: &Path) -> PathBuf {
+        self.dir
+            .join(&self.run)
+            .join(format!("{:016x}", hash(path.to_string_lossy().as_bytes())))
+    }
+}
+
+/// Restore the files replaced by the given run, or by the most recent run if run is empty.
+/// Files that have changed since they were replaced are not restored, and stay in the
+/// journal. Returns true if all files were restored.
+pub fn undo(output: &mut dyn Write, dir: &Path, run: &str, preserve: &Preserve) -> NedResult<bool> {
+    let run = if run.is_empty() {
+        let mut runs = match fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect::<Vec<String>>(),
+            Err(ref err) if err.kind() == ErrorKind::NotFound => vec![],
+            Err(err) => return Err(err.into()),
+        };
+        runs.sort();
+        runs.pop().ok_or_else(|| {
+            NedError::RunError(StringError {
+                err: format!("there are no runs to undo in {}", dir.display()),
+            })
+        })?
+    } else {
+        run.to_string()
+    };
+    let run_dir = dir.join(&run);
+    if !run_dir.is_dir() {
+        return Err(NedError::RunError(StringError {
+            err: format!("there is no run {} to undo in {}", run, dir.display()),
+        }));
+    }
+
+    let mut infos = fs::read_dir(&run_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension() == Some(OsStr::new("info")))
+        .collect::<Vec<PathBuf>>();
+    infos.sort();
+    let mut restored_all = true;
+    for info_path in infos {
+        let info = fs::read_to_string(&info_path)?;
+        let mut info = info.splitn(2, '\n');
+        let (hash_str, path) = match (info.next(), info.next()) {
+            (Some(hash), Some(path)) => (hash, PathBuf::from(path)),
+            _ => {
+                stderr_write_file_err(
+                    &info_path,
+                    &StringError {
+                        err: "is not a journal entry".to_string(),
+                    },
+                );
+                restored_all = false;
+                continue;
+            }
+        };
+        let unchanged = match fs::read(&path) {
+            Ok(content) => format!("{:016x}", hash(&content)) == hash_str,
+            Err(ref err) if err.kind() == ErrorKind::NotFound => false,
+            Err(err) => return Err(err.into()),
+        };
+        if !unchanged {
+            stderr_write_file_err(
+                &path,
+                &StringError {
+                    err: "has changed since it was replaced, not restoring it".to_string(),
+                },
+            );
+            restored_all = false;
+            continue;
+        }
+        let orig_path = info_path.with_extension("orig");
+        write_file_atomically(&path, &fs::read(&orig_path)?, preserve)?;
+        fs::remove_file(&orig_path)?;
+        fs::remove_file(&info_path)?;
+        output.write_all(&format!("restored {}\n", path.display()).into_bytes())?;
+    }
+    if restored_all {
+        fs::remove_dir_all(&run_dir)?;
+    }
+    Ok(restored_all)
+}
+
+/// The id of a run started at now by the process with the given pid. The time is given to
+/// the nanosecond and the pid is zero padded so that the ids of runs sort by when they
+/// started, even when they started in the same second.
+pub fn run_id(now: OffsetDateTime, pid: u32) -> String {
+    format!(
+        "{}-{:09}-{:010}",
+        now.format("%Y%m%d-%H%M%S"),
+        now.nanosecond(),
+        pid
+    )
+}
+
+fn default_journal_dir() -> Option<PathBuf> {
+    env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .or_else(|| {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })
+        .map(|dir| dir.join("ned").join("journal"))
+}
+
+/// 64 bit FNV-1a, which is stable across Rust versions, unlike the standard library's
+/// hashers, so it can be used in a journal written by one version of ned and read by another.
+fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
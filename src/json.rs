@@ -0,0 +1,93 @@
+//
+// ned, https://github.com/nevdelap/ned, json.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use std::fmt::Write;
+
+/// A JSON object built up one field at a time, with the fields in the order they are added.
+#[derive(Default)]
+pub struct JsonObject {
+    fields: Vec<String>,
+}
+
+impl JsonObject {
+    pub fn new() -> JsonObject {
+        JsonObject::default()
+    }
+
+    pub fn string(self, key: &str, value: &str) -> JsonObject {
+        self.value(key, &string(value))
+    }
+
+    pub fn optional_string(self, key: &str, value: Option<&str>) -> JsonObject {
+        match value {
+            Some(value) => self.string(key, value),
+            None => self.value(key, "null"),
+        }
+    }
+
+    pub fn number(self, key: &str, value: usize) -> JsonObject {
+        self.value(key, &value.to_string())
+    }
+
+    pub fn optional_number(self, key: &str, value: Option<usize>) -> JsonObject {
+        match value {
+            Some(value) => self.number(key, value),
+            None => self.value(key, "null"),
+        }
+    }
+
+    /// Add a field whose value is already JSON.
+    pub fn value(mut self, key: &str, value: &str) -> JsonObject {
+        self.fields.push(format!("{}:{}", string(key), value));
+        self
+    }
+
+    pub fn finish(self) -> String {
+        format!("{{{}}}", self.fields.join(","))
+    }
+}
+
+/// A JSON array of values that are already JSON.
+pub fn array(values: &[String]) -> String {
+    format!("[{}]", values.join(","))
+}
+
+/// A JSON string, quoted, with the characters that JSON doesn't allow in strings escaped.
+pub fn string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for char in value.chars() {
+        match char {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            '\u{8}' => json.push_str("\\b"),
+            '\u{c}' => json.push_str("\\f"),
+            char if char < ' ' => {
+                let _ = write!(json, "\\u{:04x}", char as u32);
+            }
+            char => json.push(char),
+        }
+    }
+    json.push('"');
+    json
+}
@@ -0,0 +1,72 @@
+//
+// ned, https://github.com/nevdelap/ned, links.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use crate::ned_error::StringError;
+use std::str::FromStr;
+
+/// What to do when a file to be changed is a symlink, or is in a symlinked directory.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Symlinks {
+    Skip,
+    /// Change the file that is linked to.
+    Follow,
+    /// Replace a symlink with a file, leaving the file that it linked to unchanged.
+    ReplaceLink,
+}
+
+impl FromStr for Symlinks {
+    type Err = StringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(Symlinks::Skip),
+            "follow" => Ok(Symlinks::Follow),
+            "replace-link" => Ok(Symlinks::ReplaceLink),
+            _ => Err(StringError {
+                err: format!("invalid symlinks option {}", s),
+            }),
+        }
+    }
+}
+
+/// What to do when a file to be changed has other hard links to it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hardlinks {
+    Skip,
+    /// Change the file in place so that the change is seen through all of its links.
+    Edit,
+    /// Replace the file so that its other links keep the original.
+    Break,
+}
+
+impl FromStr for Hardlinks {
+    type Err = StringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(Hardlinks::Skip),
+            "edit" => Ok(Hardlinks::Edit),
+            "break" => Ok(Hardlinks::Break),
+            _ => Err(StringError {
+                err: format!("invalid hardlinks option {}", s),
+            }),
+        }
+    }
+}
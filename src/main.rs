@@ -26,40 +26,75 @@ extern crate regex;
 extern crate time;
 extern crate walkdir;
 
+mod backup;
 mod colors;
+mod diff;
 mod files;
+mod git;
+mod hyperlinks;
+mod journal;
+mod json;
+mod links;
 mod ned_error;
 mod options_with_defaults;
 mod opts;
 mod parameters;
+mod paths;
+mod patterns;
+mod preserve;
+mod rewrite;
+mod run_state;
+mod sarif;
 mod source;
+mod stats;
+mod template;
 #[cfg(test)]
 mod tests;
 
+use crate::diff::{changes, write_diff};
 use crate::files::Files;
-use crate::ned_error::{stderr_write_file_err, NedError, NedResult};
+use crate::hyperlinks::{encode_path, hyperlink, hyperlink_path, url, url_path};
+use crate::journal::undo;
+use crate::json::{array, JsonObject};
+use crate::ned_error::{stderr_write_err, stderr_write_file_err, NedError, NedResult, StringError};
 use crate::options_with_defaults::OptionsWithDefaults;
 use crate::opts::{make_opts, usage_brief, usage_full, usage_version};
-use crate::parameters::{get_parameters, Parameters};
+use crate::parameters::{get_parameters, stdout_is_terminal, Parameters};
+use crate::paths::{absolute_path, normalize};
+use crate::patterns::PatternsInText;
+use crate::rewrite::{rewrite_file, write_file_atomically};
+use crate::run_state::RunState;
 use crate::source::Source;
 #[cfg(target_os = "windows")]
 use ansi_term::enable_ansi_support;
-use ansi_term::Colour::{Purple, Red};
+use ansi_term::Colour::{self, Blue, Cyan, Green, Purple, Red, Yellow};
 use regex::{Captures, Match, Regex};
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::fs::OpenOptions;
-use std::io::{stderr, stdin, stdout, Read, Seek, SeekFrom, Write};
+use std::fs::{self, OpenOptions};
+use std::io::{stderr, stdin, stdout, BufReader, Read, Write};
+#[cfg(test)]
+use std::io::{Seek, SeekFrom};
 use std::iter::Iterator;
+#[cfg(target_os = "windows")]
+use std::os::windows::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
 use std::string::String;
 use std::{env, process};
 
 fn main() {
     // Output is passed here so that tests can
     // call ned() directly to read the output
-    // that would go to stdout.
+    // that would go to stdout, and so is
+    // whether it is a terminal.
     let mut output = stdout();
-    let exit_code = match ned(&mut output, &env::args().skip(1).collect::<Vec<String>>()) {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let exit_code = match ned(&mut output, &args, stdout_is_terminal()) {
         Ok(exit_code) => exit_code,
+        Err(NedError::RunError(err)) => {
+            stderr_write_err(&err);
+            1
+        }
         Err(err) => {
             let _ = stderr()
                 .write_all(&format!("{}\n{}\n\n", usage_brief(), err.to_string()).into_bytes());
@@ -70,9 +105,9 @@ fn main() {
     process::exit(exit_code)
 }
 
-fn ned(output: &mut dyn Write, args: &[String]) -> NedResult<i32> {
+fn ned(output: &mut dyn Write, args: &[String], isatty: bool) -> NedResult<i32> {
     let options_with_defaults = OptionsWithDefaults::new(make_opts(), args)?;
-    let parameters = get_parameters(&options_with_defaults)?;
+    let parameters = get_parameters(&options_with_defaults, isatty)?;
 
     if parameters.version {
         let _ = output.write_all(&format!("\n{}\n", usage_version()).into_bytes());
@@ -86,6 +121,15 @@ fn ned(output: &mut dyn Write, args: &[String]) -> NedResult<i32> {
         process::exit(0);
     }
 
+    if let Some(ref run) = parameters.undo {
+        let journal = parameters
+            .journal
+            .as_ref()
+            .expect("Bug, --undo always has a journal.");
+        let restored_all = undo(output, &journal.dir, run, &parameters.preserve)?;
+        return Ok(if restored_all { 0 } else { 1 });
+    }
+
     if parameters.regex.is_none() {
         let _ = stderr().write_all(&format!("\n{}\n\n", usage_brief()).into_bytes());
         process::exit(1);
@@ -106,54 +150,215 @@ fn ned(output: &mut dyn Write, args: &[String]) -> NedResult<i32> {
         }
     }
 
-    let found_matches = process_files(output, &parameters)?;
+    let mut run_state = RunState::new(Box::new(BufReader::new(stdin())));
+    let found_matches = process_files(output, &parameters, &mut run_state)?;
+    if let Some(ref journal) = parameters.journal {
+        if journal.recorded_any() {
+            stderr_write_err(&StringError {
+                err: format!(
+                    "the replace is run {} in the journal, undo it with --undo={}",
+                    journal.run, journal.run
+                ),
+            });
+        }
+    }
+    if parameters.json && !parameters.quiet {
+        write_json_summary(output, &run_state)?;
+    } else if parameters.sarif {
+        if !parameters.quiet {
+            write_sarif_log(output, &parameters, &run_state)?;
+        }
+    } else if parameters.stats {
+        run_state.stats.write(output)?;
+    }
     Ok(if found_matches { 0 } else { 1 })
 }
 
-fn process_files(output: &mut dyn Write, parameters: &Parameters) -> NedResult<bool> {
+fn process_files(
+    output: &mut dyn Write,
+    parameters: &Parameters,
+    run_state: &mut RunState,
+) -> NedResult<bool> {
     let mut found_matches = false;
     if parameters.stdin {
         let mut source = Source::Stdin(Box::new(stdin()));
-        found_matches = process_file(output, parameters, &None, &mut source)?;
+        found_matches = process_file(output, parameters, run_state, &None, &mut source)?;
     } else {
         for glob in &parameters.globs {
-            for path_buf in &mut Files::new(parameters, &glob) {
-                match OpenOptions::new()
-                    .read(true)
-                    .write(parameters.replace.is_some())
-                    .open(path_buf.as_path())
-                {
+            run_state.walk_root = glob.clone();
+            let mut files = Files::new(parameters, &glob);
+            for path_buf in &mut files {
+                // Replacements are written to a new file which is renamed over this one, but it
+                // is opened for writing so that files that can't be written are still reported.
+                let mut open_options = OpenOptions::new();
+                open_options.read(true).write(parameters.replace.is_some());
+                // FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE, so that the file can be
+                // renamed over while it is open.
+                #[cfg(target_os = "windows")]
+                open_options.share_mode(7);
+                match open_options.open(path_buf.as_path()) {
                     Ok(file) => {
-                        let mut source = Source::File(Box::new(file));
+                        let mut source = Source::File(Box::new(file), path_buf.to_path_buf());
                         let file_name = &Some(path_buf.as_path().to_string_lossy().to_string());
-                        found_matches |=
-                            match process_file(output, parameters, &file_name, &mut source) {
-                                Ok(found_matches) => found_matches,
-                                Err(err) => {
-                                    if err.io_error_kind() == Some(std::io::ErrorKind::BrokenPipe) {
-                                        break;
-                                    }
-                                    stderr_write_file_err(&path_buf, &err);
-                                    false
+                        found_matches |= match process_file(
+                            output,
+                            parameters,
+                            run_state,
+                            &file_name,
+                            &mut source,
+                        ) {
+                            Ok(found_matches) => found_matches,
+                            Err(err) => {
+                                if err.io_error_kind() == Some(std::io::ErrorKind::BrokenPipe) {
+                                    break;
                                 }
+                                stderr_write_file_err(&path_buf, &err);
+                                run_state.stats.errors += 1;
+                                false
                             }
+                        }
+                    }
+                    Err(err) => {
+                        stderr_write_file_err(&path_buf, &err);
+                        run_state.stats.errors += 1;
                     }
-                    Err(err) => stderr_write_file_err(&path_buf, &err),
+                }
+                if run_state.quit {
+                    break;
                 }
             }
-            if parameters.quiet && found_matches {
+            run_state.stats.errors += files.errors;
+            run_state.stats.files_walked += files.walked;
+            run_state.stats.files_skipped_hidden += files.hidden;
+            run_state.stats.files_skipped_excluded += files.excluded;
+            if parameters.quiet && found_matches || run_state.quit {
                 break;
             }
             let _ = output.flush();
             let _ = stderr().flush();
         }
     }
+    if parameters.stage_rewrites() {
+        write_staged_rewrites(parameters, run_state)?;
+    }
     Ok(found_matches)
 }
 
+/// Write the rewrites that were held back until all files had been processed, unless there
+/// are more of them than --max-files or --max-replacements allow, or there were errors with
+/// --transaction, in which case none are. With --transaction, if a file can't be written the
+/// files already written are restored.
+fn write_staged_rewrites(parameters: &Parameters, run_state: &mut RunState) -> NedResult<()> {
+    let rewrites = run_state
+        .rewrites
+        .drain(..)
+        .collect::<Vec<(PathBuf, PathBuf, Vec<u8>)>>();
+    if parameters.transaction && run_state.stats.errors > 0 {
+        return Err(NedError::RunError(StringError {
+            err: format!(
+                "there were {} errors with --transaction, no files were changed",
+                run_state.stats.errors
+            ),
+        }));
+    }
+    if run_state.uncommitted_files > 0 {
+        return Err(NedError::RunError(StringError {
+            err: format!(
+                "{} files have uncommitted changes with --require-clean, no files were changed",
+                run_state.uncommitted_files
+            ),
+        }));
+    }
+    if let Some(max_files) = parameters.max_files {
+        if rewrites.len() > max_files {
+            return Err(NedError::RunError(StringError {
+                err: format!(
+                    "replacing would change {} files, more than --max-files={}, no files were \
+                     changed",
+                    rewrites.len(),
+                    max_files
+                ),
+            }));
+        }
+    }
+    if let Some(max_replacements) = parameters.max_replacements {
+        if run_state.stats.replacements > max_replacements {
+            return Err(NedError::RunError(StringError {
+                err: format!(
+                    "replacing would make {} replacements, more than --max-replacements={}, no \
+                     files were changed",
+                    run_state.stats.replacements, max_replacements
+                ),
+            }));
+        }
+    }
+    // With --transaction the originals of all of the files are read before any of them are
+    // written, so that the files already written can be restored if one can't be.
+    let mut originals = vec![];
+    for (path, _, _) in &rewrites {
+        originals.push(if parameters.transaction {
+            match fs::read(path) {
+                Ok(original) => Some(original),
+                Err(err) => {
+                    stderr_write_file_err(path, &err);
+                    return Err(NedError::RunError(StringError {
+                        err: format!(
+                            "couldn't read {} with --transaction, no files were changed",
+                            path.display()
+                        ),
+                    }));
+                }
+            }
+        } else {
+            None
+        });
+    }
+    let mut written = vec![];
+    for ((path, walk_root, bytes), original) in rewrites.into_iter().zip(originals) {
+        match rewrite_file(parameters, &path, &walk_root, &bytes) {
+            Ok(Some(written_path)) => {
+                run_state.stats.files_rewritten += 1;
+                run_state.stats.bytes_written += bytes.len();
+                if let Some(original) = original {
+                    written.push((written_path, original));
+                }
+            }
+            Ok(None) => {}
+            Err(err) => {
+                stderr_write_file_err(&path, &err);
+                if parameters.transaction {
+                    restore_written(parameters, written);
+                    return Err(NedError::RunError(StringError {
+                        err: format!(
+                            "couldn't change {} with --transaction, the files already \
+                             changed were restored",
+                            path.display()
+                        ),
+                    }));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn restore_written(parameters: &Parameters, written: Vec<(PathBuf, Vec<u8>)>) {
+    for (path, original) in written.into_iter().rev() {
+        if let Err(err) = write_file_atomically(&path, &original, &parameters.preserve) {
+            stderr_write_file_err(
+                &path,
+                &StringError {
+                    err: format!("couldn't be restored, {}", err),
+                },
+            );
+        }
+    }
+}
+
 fn process_file(
     output: &mut dyn Write,
     parameters: &Parameters,
+    run_state: &mut RunState,
     file_name: &Option<String>,
     source: &mut Source,
 ) -> NedResult<bool> {
@@ -161,18 +366,20 @@ fn process_file(
     {
         let read: &mut dyn Read = match source {
             Source::Stdin(ref mut read) => read,
-            Source::File(ref mut file) => file,
+            Source::File(ref mut file, _) => file,
             #[cfg(test)]
             Source::Cursor(ref mut cursor) => cursor,
         };
         let mut buffer = Vec::new();
         let _ = read.read_to_end(&mut buffer)?;
+        run_state.stats.bytes_read += buffer.len();
         match String::from_utf8(buffer) {
             Ok(ref parsed) => {
                 content = parsed.to_string();
             }
             Err(err) => {
                 if parameters.ignore_non_utf8 {
+                    run_state.stats.files_skipped_non_utf8 += 1;
                     return Ok(false);
                 } else {
                     return Err(NedError::from(err));
@@ -186,77 +393,293 @@ fn process_file(
         .clone()
         .expect("Bug, already checked parameters.");
 
+    run_state.stats.files_searched += 1;
+    if parameters.hyperlinks.is_some() {
+        run_state.hyperlink_path = file_name.as_deref().and_then(hyperlink_path);
+    }
+    if parameters.stats || parameters.json {
+        // Replacements are made to whole files.
+        let (matches, lines_matched) = count_matches(
+            parameters,
+            &re,
+            &content,
+            parameters.whole_files || parameters.replace.is_some(),
+        );
+        run_state.stats.matches += matches;
+        run_state.stats.lines_matched += lines_matched;
+        if matches > 0 {
+            run_state.stats.files_matched += 1;
+        }
+    }
+
     if let Some(mut replacement) = parameters.replace.clone() {
-        if parameters.colors {
+        if parameters.literal_replacement {
+            replacement = replacement.replace('$', "$$");
+        }
+        if parameters.count || parameters.count_matches {
+            // Replacements are made to whole files.
+            return write_count(
+                output, parameters, run_state, &re, file_name, &content, true,
+            );
+        }
+        if let Source::File(_, ref path) = source {
+            if parameters.writes_files()
+                && !parameters.force_dirty
+                && run_state.git_status.has_uncommitted_changes(path)
+            {
+                let found_matches = re.is_match(&content);
+                if found_matches {
+                    let err = if parameters.require_clean {
+                        run_state.uncommitted_files += 1;
+                        "has uncommitted changes"
+                    } else {
+                        "has uncommitted changes, not changing it, use --force-dirty to change it"
+                    };
+                    stderr_write_file_err(
+                        path,
+                        &StringError {
+                            err: err.to_string(),
+                        },
+                    );
+                }
+                return Ok(found_matches);
+            }
+        }
+        // Diffs, changes, and interactive questions color replacements their own way.
+        if parameters.colors
+            && parameters.diff.is_none()
+            && !parameters.show_changes
+            && !parameters.interactive
+        {
             replacement = Red.bold().paint(replacement.as_str()).to_string();
         }
         if parameters.case_replacements {
             replacement = replace_case_escape_sequences_with_special_strings(&replacement);
         }
-        let (content, found_matches) = replace(parameters, &re, &content, &replacement);
-        let content = if parameters.case_replacements {
-            replace_case_with_special_strings(&content)
+        let (new_content, replacements) = if parameters.interactive {
+            replace_interactively(
+                output,
+                parameters,
+                run_state,
+                &re,
+                file_name,
+                &content,
+                &replacement,
+            )?
         } else {
-            content
+            replace(parameters, &re, &content, &replacement)
         };
-        if parameters.stdout {
+        let found_matches = replacements > 0;
+        let new_content = if parameters.case_replacements {
+            replace_case_with_special_strings(&new_content)
+        } else {
+            new_content
+        };
+        run_state.stats.replacements += replacements;
+        if parameters.json && !parameters.quiet {
+            write_json_replacements(output, parameters, file_name, replacements, &new_content)?;
+        }
+        if let Some(context) = parameters.diff {
+            if !parameters.quiet {
+                let file_name = file_name.clone().unwrap_or_else(|| "stdin".to_string());
+                let (old_name, new_name) = if parameters.patch {
+                    // Relative to where ned is run, for git apply and patch -p1.
+                    let path = relative_path(&file_name).ok_or_else(|| {
+                        NedError::from(
+                            "is outside the current directory, which --patch paths are \
+                             relative to"
+                                .to_string(),
+                        )
+                    })?;
+                    (format!("a/{}", path), format!("b/{}", path))
+                } else {
+                    (file_name.clone(), file_name)
+                };
+                write_diff(
+                    output,
+                    parameters.colors,
+                    &old_name,
+                    &new_name,
+                    &content,
+                    &new_content,
+                    context,
+                )?;
+            }
+        } else if parameters.show_changes {
             if !parameters.quiet {
-                write_file_name_and_line_number(output, parameters, file_name, None)?;
-                output.write_all(&content.into_bytes())?;
+                write_changes(
+                    output,
+                    parameters,
+                    run_state,
+                    file_name,
+                    &content,
+                    &new_content,
+                )?;
+            }
+        } else if parameters.stdout {
+            if !parameters.quiet && !parameters.json {
+                write_file_name_and_line_number(
+                    output, parameters, run_state, file_name, None, None, false,
+                )?;
+                output.write_all(&new_content.into_bytes())?;
             }
         } else {
             // It's not a single match in test.
             #[allow(clippy::single_match)]
             match source {
                 // A better way???
-                Source::File(ref mut file) => {
+                Source::File(_, ref path) => {
                     if found_matches {
-                        file.seek(SeekFrom::Start(0))?;
-                        let bytes = &content.into_bytes();
-                        file.write_all(bytes)?;
-                        file.set_len(bytes.len() as u64)?;
+                        if parameters.stage_rewrites() {
+                            run_state.rewrites.push((
+                                path.clone(),
+                                run_state.walk_root.clone(),
+                                new_content.into_bytes(),
+                            ));
+                        } else if rewrite_file(
+                            parameters,
+                            path,
+                            &run_state.walk_root,
+                            new_content.as_bytes(),
+                        )?
+                        .is_some()
+                        {
+                            run_state.stats.files_rewritten += 1;
+                            run_state.stats.bytes_written += new_content.len();
+                        }
                     }
                 }
                 #[cfg(test)]
                 Source::Cursor(ref mut cursor) => {
                     cursor.seek(SeekFrom::Start(0))?;
-                    cursor.write_all(&content.into_bytes())?;
+                    cursor.write_all(&new_content.into_bytes())?;
                 }
                 _ => {}
             }
         }
         Ok(found_matches)
+    } else if parameters.count || parameters.count_matches {
+        write_count(
+            output,
+            parameters,
+            run_state,
+            &re,
+            file_name,
+            &content,
+            parameters.whole_files,
+        )
+    } else if parameters.json {
+        write_json_matches(output, parameters, &re, file_name, &content)
+    } else if parameters.sarif {
+        Ok(add_sarif_results(
+            parameters, run_state, &re, file_name, &content,
+        ))
+    } else if parameters.vimgrep {
+        write_vimgrep_matches(output, parameters, run_state, &re, file_name, &content)
+    } else if let Some(ref format) = parameters.format {
+        write_formatted_matches(output, parameters, &re, file_name, &content, format)
     } else if parameters.file_names_only {
         let found_matches = re.is_match(&content);
         if found_matches ^ parameters.no_match {
-            write_file_name_and_line_number(output, parameters, file_name, None)?;
+            write_file_name_and_line_number(
+                output, parameters, run_state, file_name, None, None, false,
+            )?;
         }
         Ok(found_matches)
     } else if !parameters.whole_files {
-        let mut found_matches = false;
-        let context_map = make_context_map(&parameters, &re, &content);
-        for (index, line) in content.lines().enumerate() {
-            let line_number = index + 1;
-            found_matches |= process_text(
-                output,
-                parameters,
-                &re,
-                file_name,
-                Some(line_number),
-                line,
-                Some(&context_map),
-            )?;
-            if parameters.quiet && found_matches {
-                break;
-            }
+        // With --heading the lines are held back so that the file name can be shown once
+        // before them, if there are any.
+        let mut lines_output = vec![];
+        let found_matches = process_lines(
+            if parameters.heading {
+                &mut lines_output
+            } else {
+                &mut *output
+            },
+            parameters,
+            run_state,
+            &re,
+            file_name,
+            &content,
+        )?;
+        if !lines_output.is_empty() {
+            write_heading(output, parameters, run_state, file_name)?;
+            output.write_all(&lines_output)?;
         }
         Ok(found_matches)
     } else {
-        let found_matches = process_text(output, parameters, &re, file_name, None, &content, None)?;
+        let found_matches = process_text(
+            output, parameters, run_state, &re, file_name, None, 0, &content, None,
+        )?;
         Ok(found_matches)
     }
 }
 
+/// Process the lines of the content, showing the lines with matches, or without them with
+/// -v/--no-match, and those around them, as specified in the parameters.
+fn process_lines(
+    output: &mut dyn Write,
+    parameters: &Parameters,
+    run_state: &mut RunState,
+    re: &Regex,
+    file_name: &Option<String>,
+    content: &str,
+) -> NedResult<bool> {
+    let mut found_matches = false;
+    let context_map = make_context_map(parameters, re, content);
+    // Context lines aren't shown with -v/--no-match or -g/--group.
+    let separate_context = (parameters.context_before > 0 || parameters.context_after > 0)
+        && !parameters.no_match
+        && parameters.group.is_none()
+        && !parameters.quiet;
+    let mut last_line_shown = None;
+    for (index, (offset, line)) in lines_with_offsets(content).into_iter().enumerate() {
+        let line_number = index + 1;
+        if separate_context && context_map[index] {
+            let separate = match last_line_shown {
+                Some(last_line_shown) => last_line_shown + 1 < line_number,
+                // With --heading files are separated by their headings.
+                None => run_state.context_shown && !parameters.heading,
+            };
+            if separate {
+                write_context_separator(output, parameters)?;
+            }
+            last_line_shown = Some(line_number);
+            run_state.context_shown = true;
+        }
+        found_matches |= process_text(
+            output,
+            parameters,
+            run_state,
+            re,
+            file_name,
+            Some(line_number),
+            offset,
+            line,
+            Some(&context_map),
+        )?;
+        if parameters.quiet && found_matches {
+            break;
+        }
+    }
+    Ok(found_matches)
+}
+
+/// The byte offset in the content, and the text without its line ending, of each line, as
+/// content.lines() gives them.
+fn lines_with_offsets(content: &str) -> Vec<(usize, &str)> {
+    let mut offset = 0;
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            let line_offset = offset;
+            offset += line.len();
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            (line_offset, line.strip_suffix('\r').unwrap_or(line))
+        })
+        .collect()
+}
+
 /// Returns a vector whose capacity equals the number of lines in the file, and whose
 /// value is a boolean that indicates whether or not that line should be shown given
 /// the -C --context, -B --before, and -A --after options specified in the parameters.
@@ -295,12 +718,15 @@ fn is_match_with_number_skip_backwards(parameters: &Parameters, re: &Regex, text
     false
 }
 
+#[allow(clippy::too_many_arguments)]
 fn process_text(
     output: &mut dyn Write,
     parameters: &Parameters,
+    run_state: &RunState,
     re: &Regex,
     file_name: &Option<String>,
     line_number: Option<usize>,
+    offset: usize,
     text: &str,
     context_map: Option<&Vec<bool>>,
 ) -> NedResult<bool> {
@@ -308,18 +734,47 @@ fn process_text(
         // Quiet match only is shortcut by the more performant is_match() .
         return Ok(re.is_match(&text));
     }
+    let position = first_match_position(parameters, re, offset, text);
     if let Some(ref group) = parameters.group {
         // TODO 2: make it respect -n, -k, -b TO TEST
-        return write_groups(output, parameters, &re, file_name, line_number, text, group);
+        return write_groups(
+            output,
+            parameters,
+            run_state,
+            &re,
+            file_name,
+            line_number,
+            position,
+            text,
+            group,
+        );
     } else if parameters.no_match {
         let found_matches = re.is_match(&text);
         if !found_matches {
-            write_line(output, parameters, file_name, line_number, &text)?;
+            write_line(
+                output,
+                parameters,
+                run_state,
+                file_name,
+                line_number,
+                None,
+                false,
+                &text,
+            )?;
         }
         return Ok(found_matches);
     } else if re.is_match(text) {
         if parameters.matches_only {
-            if write_matches(output, parameters, &re, file_name, line_number, text)? {
+            if write_matches(
+                output,
+                parameters,
+                run_state,
+                &re,
+                file_name,
+                line_number,
+                position,
+                text,
+            )? {
                 return Ok(true);
             }
         } else {
@@ -328,7 +783,16 @@ fn process_text(
             let (text, found_matches) =
                 color_matches_with_number_skip_backwards(parameters, re, text);
             if found_matches {
-                write_line(output, parameters, file_name, line_number, &text)?;
+                write_line(
+                    output,
+                    parameters,
+                    run_state,
+                    file_name,
+                    line_number,
+                    position,
+                    false,
+                    &text,
+                )?;
                 return Ok(true);
             }
         }
@@ -337,7 +801,16 @@ fn process_text(
     if let Some(line_number) = line_number {
         if let Some(context_map) = context_map {
             if !context_map.is_empty() && context_map[line_number - 1] {
-                write_line(output, parameters, file_name, Some(line_number), text)?;
+                write_line(
+                    output,
+                    parameters,
+                    run_state,
+                    file_name,
+                    Some(line_number),
+                    None,
+                    true,
+                    text,
+                )?;
             }
         }
     }
@@ -345,13 +818,19 @@ fn process_text(
 }
 
 /// Do a replace_all() or a find_iter() taking into account which of --number, --skip, and
-/// --backwards have been specified.
-fn replace(parameters: &Parameters, re: &Regex, text: &str, replace: &str) -> (String, bool) {
-    let mut found_matches = false;
+/// --backwards have been specified, returning the new text and the number of replacements.
+fn replace(parameters: &Parameters, re: &Regex, text: &str, replace: &str) -> (String, usize) {
+    let mut replacements = 0;
     let mut new_text;
     if !parameters.limit_matches() {
-        found_matches = re.is_match(text);
-        new_text = re.replace_all(text, replace).into_owned()
+        new_text = re
+            .replace_all(text, |captures: &Captures| {
+                replacements += 1;
+                let mut this_replace = String::new();
+                captures.expand(replace, &mut this_replace);
+                this_replace
+            })
+            .into_owned()
     } else {
         new_text = text.to_string();
         let start_end_byte_indices = re.find_iter(&text).collect::<Vec<Match>>();
@@ -360,7 +839,7 @@ fn replace(parameters: &Parameters, re: &Regex, text: &str, replace: &str) -> (S
         for (rev_index, &_match) in start_end_byte_indices.iter().rev().enumerate() {
             let index = count - rev_index - 1;
             if parameters.include_match(index, count) {
-                found_matches = true;
+                replacements += 1;
                 let this_replace = re.replace(_match.as_str(), replace).into_owned();
                 new_text = format!(
                     "{}{}{}",
@@ -373,7 +852,166 @@ fn replace(parameters: &Parameters, re: &Regex, text: &str, replace: &str) -> (S
             }
         }
     };
-    (new_text, found_matches)
+    (new_text, replacements)
+}
+
+enum Answer {
+    Yes,
+    No,
+    All,
+    Quit,
+}
+
+/// Do a replace taking into account which of --number, --skip, and --backwards have been
+/// specified, asking before replacing each match.
+fn replace_interactively(
+    output: &mut dyn Write,
+    parameters: &Parameters,
+    run_state: &mut RunState,
+    re: &Regex,
+    file_name: &Option<String>,
+    text: &str,
+    replace: &str,
+) -> NedResult<(String, usize)> {
+    let mut replacements = 0;
+    let mut new_text = String::with_capacity(text.len());
+    let mut last_end = 0;
+    let mut replace_rest_of_file = false;
+    let captures = re.captures_iter(text).collect::<Vec<Captures>>();
+    let count = captures.len();
+    let patterns = parameters.patterns.in_text(text);
+    for (index, capture) in captures.iter().enumerate() {
+        let _match = capture.get(0).expect("Group 0 is always the whole match.");
+        let mut this_replace = String::new();
+        capture.expand(replace, &mut this_replace);
+        let replace_this = parameters.include_match(index, count)
+            && !run_state.quit
+            && (replace_rest_of_file
+                || match ask_replace(
+                    output,
+                    parameters,
+                    run_state,
+                    file_name,
+                    text,
+                    &patterns,
+                    &_match,
+                    &this_replace,
+                )? {
+                    Answer::Yes => true,
+                    Answer::No => false,
+                    Answer::All => {
+                        replace_rest_of_file = true;
+                        true
+                    }
+                    Answer::Quit => {
+                        run_state.quit = true;
+                        false
+                    }
+                });
+        new_text.push_str(&text[last_end.._match.start()]);
+        if replace_this {
+            replacements += 1;
+            new_text.push_str(&this_replace);
+        } else {
+            new_text.push_str(_match.as_str());
+        }
+        last_end = _match.end();
+    }
+    new_text.push_str(&text[last_end..]);
+    Ok((new_text, replacements))
+}
+
+/// Show the lines a match is on with the -B/--before and -A/--after lines around them, and
+/// the same lines with the replacement made, and ask whether to make it.
+#[allow(clippy::too_many_arguments)]
+fn ask_replace(
+    output: &mut dyn Write,
+    parameters: &Parameters,
+    run_state: &mut RunState,
+    file_name: &Option<String>,
+    text: &str,
+    patterns: &PatternsInText,
+    _match: &Match,
+    replace: &str,
+) -> NedResult<Answer> {
+    let start = text[.._match.start()]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let end = text[_match.end()..]
+        .find('\n')
+        .map_or(text.len(), |index| _match.end() + index);
+    let line_number = text[..start].matches('\n').count() + 1;
+    let before_lines = text[..start].lines().collect::<Vec<&str>>();
+    let before_lines =
+        &before_lines[before_lines.len().saturating_sub(parameters.context_before)..];
+    let after_text = text[end..].strip_prefix('\n').unwrap_or("");
+    let after_lines = after_text
+        .lines()
+        .take(parameters.context_after)
+        .collect::<Vec<&str>>();
+
+    let old_lines = format!(
+        "{}{}{}",
+        &text[start.._match.start()],
+        color_match(parameters, patterns, _match),
+        &text[_match.end()..end]
+    );
+    let replace = if parameters.colors {
+        Green.bold().paint(replace).to_string()
+    } else {
+        replace.to_string()
+    };
+    let mut new_lines = format!(
+        "{}{}{}",
+        &text[start.._match.start()],
+        replace,
+        &text[_match.end()..end]
+    );
+    if parameters.case_replacements {
+        new_lines = replace_case_with_special_strings(&new_lines);
+    }
+
+    write_file_name_and_line_number(
+        output,
+        parameters,
+        run_state,
+        file_name,
+        Some(line_number),
+        None,
+        false,
+    )?;
+    let mut question = String::new();
+    for line in before_lines {
+        question.push_str(&format!(" {}\n", line));
+    }
+    for line in old_lines.split('\n') {
+        question.push_str(&format!("-{}\n", line));
+    }
+    for line in new_lines.split('\n') {
+        question.push_str(&format!("+{}\n", line));
+    }
+    for line in after_lines {
+        question.push_str(&format!(" {}\n", line));
+    }
+    output.write_all(&question.into_bytes())?;
+
+    loop {
+        output.write_all(b"Replace? [y,n,a,q] ")?;
+        output.flush()?;
+        let mut answer = String::new();
+        if run_state.input.read_line(&mut answer)? == 0 {
+            // There's no one to answer.
+            output.write_all(b"\n")?;
+            return Ok(Answer::Quit);
+        }
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => return Ok(Answer::Yes),
+            "n" | "no" => return Ok(Answer::No),
+            "a" | "all" => return Ok(Answer::All),
+            "q" | "quit" => return Ok(Answer::Quit),
+            _ => {}
+        }
+    }
 }
 
 enum CaseEscape {
@@ -458,15 +1096,66 @@ fn title_case(str: &str) -> String {
     result
 }
 
+/// Where a match is, for --column, --byte-offset, and --vimgrep.
+#[derive(Clone, Copy)]
+struct Position {
+    /// From 1, of the line the match starts on in the text, which is the line number in the
+    /// file with -w/--whole-files.
+    line: usize,
+    /// In characters, from 1, in the line the match starts on.
+    column: usize,
+    /// In bytes, from 0, from the start of the file.
+    byte_offset: usize,
+}
+
+/// Where the first match that --number, --skip, and --backwards include in the text is, if
+/// --column or --byte-offset was specified. The offset is that of the text in the file.
+fn first_match_position(
+    parameters: &Parameters,
+    re: &Regex,
+    offset: usize,
+    text: &str,
+) -> Option<Position> {
+    if !parameters.column && !parameters.byte_offset {
+        return None;
+    }
+    let matches = re.find_iter(text).collect::<Vec<Match>>();
+    let count = matches.len();
+    let (_, _match) = matches
+        .iter()
+        .enumerate()
+        .find(|&(index, _)| parameters.include_match(index, count))?;
+    let start = _match.start();
+    // With -w/--whole-files the column is in the line the match starts on.
+    let line_start = text[..start].rfind('\n').map_or(0, |index| index + 1);
+    Some(Position {
+        line: text[..line_start].matches('\n').count() + 1,
+        column: text[line_start..start].chars().count() + 1,
+        byte_offset: offset + start,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 fn write_line(
     output: &mut dyn Write,
     parameters: &Parameters,
+    run_state: &RunState,
     file_name: &Option<String>,
     line_number: Option<usize>,
+    position: Option<Position>,
+    context: bool,
     text: &str,
 ) -> NedResult<()> {
     if !parameters.quiet {
-        write_file_name_and_line_number(output, parameters, file_name, line_number)?;
+        write_file_name_and_line_number(
+            output,
+            parameters,
+            run_state,
+            file_name,
+            line_number,
+            position,
+            context,
+        )?;
         if !parameters.line_numbers_only && !parameters.quiet {
             output.write_all(&text.to_string().into_bytes())?;
             write_newline_if_replaced_text_ends_with_newline(output, &text)?;
@@ -475,12 +1164,15 @@ fn write_line(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn write_groups(
     output: &mut dyn Write,
     parameters: &Parameters,
+    run_state: &RunState,
     re: &Regex,
     file_name: &Option<String>,
     line_number: Option<usize>,
+    position: Option<Position>,
     text: &str,
     group: &str,
 ) -> NedResult<bool> {
@@ -501,8 +1193,11 @@ fn write_groups(
                         write_file_name_and_line_number(
                             output,
                             parameters,
+                            run_state,
                             file_name,
                             line_number,
+                            position,
+                            false,
                         )?;
                         wrote_file_name = true;
                     }
@@ -521,26 +1216,38 @@ fn write_groups(
 
 /// Write matches taking into account which of --number, --skip, and --backwards have been
 /// specified.
+#[allow(clippy::too_many_arguments)]
 fn write_matches(
     output: &mut dyn Write,
     parameters: &Parameters,
+    run_state: &RunState,
     re: &Regex,
     file_name: &Option<String>,
     line_number: Option<usize>,
+    position: Option<Position>,
     text: &str,
 ) -> NedResult<bool> {
     let mut found_matches = false;
     let mut file_name_written = false;
     let start_end_byte_indices = re.find_iter(text).collect::<Vec<Match>>();
     let count = start_end_byte_indices.len();
+    let patterns = parameters.patterns.in_text(text);
     for (index, &_match) in start_end_byte_indices.iter().enumerate() {
         if parameters.include_match(index, count) {
             found_matches = true;
             if !file_name_written {
-                write_file_name_and_line_number(output, parameters, file_name, line_number)?;
+                write_file_name_and_line_number(
+                    output,
+                    parameters,
+                    run_state,
+                    file_name,
+                    line_number,
+                    position,
+                    false,
+                )?;
                 file_name_written = true;
             }
-            let text = color(parameters, &text[_match.start().._match.end()]);
+            let text = color_match(parameters, &patterns, &_match);
             if !parameters.quiet {
                 output.write_all(&text.to_string().into_bytes())?;
             } else {
@@ -554,40 +1261,540 @@ fn write_matches(
     Ok(found_matches)
 }
 
+/// Count the matches that --number, --skip, and --backwards include in each line of the
+/// content, or in the whole content if whole is true, and the lines they start on, returning
+/// the number of matches and the number of lines.
+fn count_matches(
+    parameters: &Parameters,
+    re: &Regex,
+    content: &str,
+    whole: bool,
+) -> (usize, usize) {
+    let texts = if whole {
+        vec![(0, content)]
+    } else {
+        lines_with_offsets(content)
+    };
+    let mut matches = 0;
+    let mut lines_with_matches = 0;
+    // A match after the last line ending, or in empty content, as there can be with -w, isn't
+    // on a line as lines() counts them, so it isn't counted as a line with a match, which
+    // -v/--no-match takes from the number of lines.
+    let lines = content.lines().count();
+    // The line that the last match started on, for counting the lines that matches start on.
+    let mut line_number = 0;
+    let mut last_counted_line_number = None;
+    let mut last_start = 0;
+    for (text_offset, text) in texts {
+        let text_matches = re.find_iter(text).collect::<Vec<Match>>();
+        let count = text_matches.len();
+        for (index, _match) in text_matches.iter().enumerate() {
+            if !parameters.include_match(index, count) {
+                continue;
+            }
+            matches += 1;
+            let start = text_offset + _match.start();
+            line_number += content[last_start..start].matches('\n').count();
+            last_start = start;
+            if line_number < lines && last_counted_line_number != Some(line_number) {
+                lines_with_matches += 1;
+                last_counted_line_number = Some(line_number);
+            }
+        }
+    }
+    (matches, lines_with_matches)
+}
+
+/// Write the number of lines with matches, of lines without matches with -v/--no-match, or of
+/// matches with --count-matches, in the content, with its file name, if it isn't 0. Matches
+/// are those that --number, --skip, and --backwards include in each line, or in the whole
+/// content if whole is true.
+fn write_count(
+    output: &mut dyn Write,
+    parameters: &Parameters,
+    run_state: &RunState,
+    re: &Regex,
+    file_name: &Option<String>,
+    content: &str,
+    whole: bool,
+) -> NedResult<bool> {
+    let (matches, lines_with_matches) = count_matches(parameters, re, content, whole);
+    let count = if parameters.count_matches {
+        matches
+    } else if parameters.no_match {
+        content.lines().count() - lines_with_matches
+    } else {
+        lines_with_matches
+    };
+    if count > 0 && !parameters.quiet {
+        write_file_name_and_line_number(
+            output, parameters, run_state, file_name, None, None, false,
+        )?;
+        output.write_all(&format!("{}\n", count).into_bytes())?;
+    }
+    Ok(matches > 0)
+}
+
+/// The byte offset in the content and the text of each line, or of the whole content with
+/// -w/--whole-files, within which --number, --skip, and --backwards count matches.
+fn match_texts<'a>(parameters: &Parameters, content: &'a str) -> Vec<(usize, &'a str)> {
+    if parameters.whole_files {
+        vec![(0, content)]
+    } else {
+        lines_with_offsets(content)
+    }
+}
+
+/// The line number, the column, in characters, and the text without its line ending, of the
+/// line that a match starting at the byte offset start in the content starts on.
+fn line_of_match(content: &str, start: usize) -> (usize, usize, &str) {
+    let line_start = content[..start].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = content[start..]
+        .find('\n')
+        .map_or(content.len(), |index| start + index);
+    let line_number = content[..line_start].matches('\n').count() + 1;
+    let column = content[line_start..start].chars().count() + 1;
+    let line = &content[line_start..line_end];
+    (line_number, column, line.strip_suffix('\r').unwrap_or(line))
+}
+
+/// Write a line for each match, with its file name, line number and column, in characters, and
+/// the line it starts on, as vim's :vimgrep and grep-mode in Emacs read them.
+fn write_vimgrep_matches(
+    output: &mut dyn Write,
+    parameters: &Parameters,
+    run_state: &RunState,
+    re: &Regex,
+    file_name: &Option<String>,
+    content: &str,
+) -> NedResult<bool> {
+    let mut found_matches = false;
+    for (text_offset, text) in match_texts(parameters, content) {
+        let matches = re.find_iter(text).collect::<Vec<Match>>();
+        let count = matches.len();
+        for (index, _match) in matches.iter().enumerate() {
+            if !parameters.include_match(index, count) {
+                continue;
+            }
+            found_matches = true;
+            if parameters.quiet {
+                return Ok(found_matches);
+            }
+            let start = text_offset + _match.start();
+            let (line_number, column, line) = line_of_match(content, start);
+            write_file_name_and_line_number(
+                output,
+                parameters,
+                run_state,
+                file_name,
+                Some(line_number),
+                Some(Position {
+                    line: line_number,
+                    column,
+                    byte_offset: start,
+                }),
+                false,
+            )?;
+            output.write_all(&color_matches_all(parameters, re, line).into_bytes())?;
+            output.write_all(b"\n")?;
+        }
+    }
+    Ok(found_matches)
+}
+
+/// Write the --format template rendered for each match, followed by a newline.
+fn write_formatted_matches(
+    output: &mut dyn Write,
+    parameters: &Parameters,
+    re: &Regex,
+    file_name: &Option<String>,
+    content: &str,
+    format: &str,
+) -> NedResult<bool> {
+    let path = file_name.as_deref().unwrap_or("stdin");
+    let mut found_matches = false;
+    for (text_offset, text) in match_texts(parameters, content) {
+        let captures = re.captures_iter(text).collect::<Vec<Captures>>();
+        let count = captures.len();
+        let patterns = parameters.patterns.in_text(text);
+        for (index, capture) in captures.iter().enumerate() {
+            if !parameters.include_match(index, count) {
+                continue;
+            }
+            found_matches = true;
+            if parameters.quiet {
+                return Ok(found_matches);
+            }
+            let _match = capture
+                .get(0)
+                .expect("Bug, group 0 is always the whole match.");
+            let start = text_offset + _match.start();
+            let (line_number, column, line) = line_of_match(content, start);
+            let mut formatted = template::render(format, |placeholder| match placeholder {
+                "path" => Some(path.to_string()),
+                "line" => Some(line_number.to_string()),
+                "column" => Some(column.to_string()),
+                "byte_offset" => Some(start.to_string()),
+                "match" => Some(_match.as_str().to_string()),
+                "line_text" => Some(line.to_string()),
+                "pattern" => Some(patterns.index_of(_match.start()).to_string()),
+                group => {
+                    // Groups that didn't take part in the match are empty.
+                    let is_group = match group.parse::<usize>() {
+                        Ok(index) => index < capture.len(),
+                        Err(_) => re.capture_names().any(|name| name == Some(group)),
+                    };
+                    if is_group {
+                        let _match = match group.parse::<usize>() {
+                            Ok(index) => capture.get(index),
+                            Err(_) => capture.name(group),
+                        };
+                        Some(_match.map_or("", |_match| _match.as_str()).to_string())
+                    } else {
+                        None
+                    }
+                }
+            });
+            formatted.push('\n');
+            output.write_all(&formatted.into_bytes())?;
+        }
+    }
+    Ok(found_matches)
+}
+
+/// Write the lines of a file with matches, or the whole file with -w/--whole-files, and the
+/// context lines around them, as --json match and context events between begin and end
+/// events, if it has matches.
+fn write_json_matches(
+    output: &mut dyn Write,
+    parameters: &Parameters,
+    re: &Regex,
+    file_name: &Option<String>,
+    content: &str,
+) -> NedResult<bool> {
+    let path = file_name.as_deref();
+    // The line number, the byte offset in the file, and the text of each line.
+    let lines = if parameters.whole_files {
+        vec![(None, 0, content)]
+    } else {
+        lines_with_offsets(content)
+            .into_iter()
+            .enumerate()
+            .map(|(index, (offset, line))| (Some(index + 1), offset, line))
+            .collect::<Vec<(Option<usize>, usize, &str)>>()
+    };
+    let context_map = if parameters.whole_files || parameters.no_match {
+        None
+    } else {
+        Some(make_context_map(parameters, re, content))
+    };
+
+    let mut found_matches = false;
+    let mut matching_lines = 0;
+    let mut match_count = 0;
+    let mut events = vec![];
+    for (index, &(line_number, offset, text)) in lines.iter().enumerate() {
+        let matches = json_matches(parameters, re, text);
+        found_matches |= !matches.is_empty();
+        let event_type = if matches.is_empty() == parameters.no_match {
+            matching_lines += 1;
+            match_count += matches.len();
+            "match"
+        } else if context_map.as_ref().is_some_and(|map| map[index]) {
+            "context"
+        } else {
+            continue;
+        };
+        let mut event = JsonObject::new()
+            .string("type", event_type)
+            .optional_string("path", path)
+            .optional_number("line_number", line_number)
+            .number("offset", offset)
+            .string("text", text);
+        if event_type == "match" {
+            event = event.value("matches", &array(&matches));
+        }
+        events.push(event.finish());
+    }
+
+    if matching_lines > 0 && !parameters.quiet {
+        write_json_event(
+            output,
+            JsonObject::new()
+                .string("type", "begin")
+                .optional_string("path", path),
+        )?;
+        for event in events {
+            output.write_all(&format!("{}\n", event).into_bytes())?;
+        }
+        write_json_event(
+            output,
+            JsonObject::new()
+                .string("type", "end")
+                .optional_string("path", path)
+                .number("lines", matching_lines)
+                .number("matches", match_count),
+        )?;
+    }
+    Ok(found_matches)
+}
+
+/// The matches in the text that --number, --skip, and --backwards include, as JSON objects
+/// with their text, their byte offsets in the text, the index of their pattern, and their
+/// numbered and named groups.
+fn json_matches(parameters: &Parameters, re: &Regex, text: &str) -> Vec<String> {
+    let names = re.capture_names().collect::<Vec<Option<&str>>>();
+    let captures = re.captures_iter(text).collect::<Vec<Captures>>();
+    let count = captures.len();
+    let patterns = parameters.patterns.in_text(text);
+    captures
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| parameters.include_match(index, count))
+        .map(|(_, capture)| {
+            let _match = capture.get(0).expect("Group 0 is always the whole match.");
+            let groups = (1..capture.len())
+                .map(|group| {
+                    let group_match = capture.get(group);
+                    JsonObject::new()
+                        .number("group", group)
+                        .optional_string("name", names[group])
+                        .optional_string("text", group_match.map(|m| m.as_str()))
+                        .optional_number("start", group_match.map(|m| m.start()))
+                        .optional_number("end", group_match.map(|m| m.end()))
+                        .finish()
+                })
+                .collect::<Vec<String>>();
+            JsonObject::new()
+                .string("text", _match.as_str())
+                .number("start", _match.start())
+                .number("end", _match.end())
+                .number("pattern", patterns.index_of(_match.start()))
+                .value("groups", &array(&groups))
+                .finish()
+        })
+        .collect()
+}
+
+/// Write the replacements made in a file as a --json replace event between begin and end
+/// events, with the replaced content if it is going to stdout, if it has replacements.
+fn write_json_replacements(
+    output: &mut dyn Write,
+    parameters: &Parameters,
+    file_name: &Option<String>,
+    replacements: usize,
+    new_content: &str,
+) -> NedResult<()> {
+    let path = file_name.as_deref();
+    if replacements > 0 {
+        write_json_event(
+            output,
+            JsonObject::new()
+                .string("type", "begin")
+                .optional_string("path", path),
+        )?;
+        let mut event = JsonObject::new()
+            .string("type", "replace")
+            .optional_string("path", path)
+            .number("replacements", replacements);
+        if parameters.stdout {
+            event = event.string("content", new_content);
+        }
+        write_json_event(output, event)?;
+        write_json_event(
+            output,
+            JsonObject::new()
+                .string("type", "end")
+                .optional_string("path", path)
+                .number("replacements", replacements),
+        )?;
+    }
+    Ok(())
+}
+
+fn write_json_summary(output: &mut dyn Write, run_state: &RunState) -> NedResult<()> {
+    write_json_event(
+        output,
+        run_state
+            .stats
+            .add_to_json(JsonObject::new().string("type", "summary")),
+    )
+}
+
+/// Add a --sarif result for each match that --number, --skip, and --backwards include, to be
+/// written at the end.
+fn add_sarif_results(
+    parameters: &Parameters,
+    run_state: &mut RunState,
+    re: &Regex,
+    file_name: &Option<String>,
+    content: &str,
+) -> bool {
+    let file_name = file_name.as_deref().unwrap_or("stdin");
+    let uri = match relative_path(file_name) {
+        Some(path) => encode_path(&path),
+        // Files outside where ned is run are given by their absolute paths.
+        None => format!("file:///{}", url_path(&absolute_path(Path::new(file_name)))),
+    };
+    let mut found_matches = false;
+    for (text_offset, text) in match_texts(parameters, content) {
+        let matches = re.find_iter(text).collect::<Vec<Match>>();
+        let count = matches.len();
+        let patterns = parameters.patterns.in_text(text);
+        for (index, _match) in matches.iter().enumerate() {
+            if !parameters.include_match(index, count) {
+                continue;
+            }
+            found_matches = true;
+            if parameters.quiet {
+                return found_matches;
+            }
+            let (start_line, start_column, _) =
+                line_of_match(content, text_offset + _match.start());
+            let (end_line, end_column, _) = line_of_match(content, text_offset + _match.end());
+            let rule_index = patterns.index_of(_match.start());
+            run_state.sarif_results.push(sarif::result(
+                &sarif_rule_id(parameters, rule_index),
+                rule_index,
+                &uri,
+                (start_line, start_column),
+                (end_line, end_column),
+                _match.as_str(),
+            ));
+        }
+    }
+    found_matches
+}
+
+/// The --sarif-rule for the pattern with the index, or the pattern if there isn't one.
+fn sarif_rule_id(parameters: &Parameters, index: usize) -> String {
+    match parameters.sarif_rules.get(index) {
+        Some(rule) => rule.clone(),
+        None => parameters.patterns.patterns[index].clone(),
+    }
+}
+
+fn write_sarif_log(
+    output: &mut dyn Write,
+    parameters: &Parameters,
+    run_state: &RunState,
+) -> NedResult<()> {
+    let rules = parameters
+        .patterns
+        .patterns
+        .iter()
+        .enumerate()
+        .map(|(index, pattern)| (sarif_rule_id(parameters, index), pattern.as_str()))
+        .collect::<Vec<(String, &str)>>();
+    let log = sarif::log(&rules, &run_state.sarif_results);
+    output.write_all(&format!("{}\n", log).into_bytes())?;
+    Ok(())
+}
+
+/// A file's path relative to where ned is run, with / separators, or None if it is outside of
+/// it.
+fn relative_path(file_name: &str) -> Option<String> {
+    let current_dir = normalize(&env::current_dir().ok()?);
+    let path = absolute_path(Path::new(file_name));
+    let relative = path.strip_prefix(&current_dir).ok()?;
+    Some(
+        relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<Cow<str>>>()
+            .join("/"),
+    )
+}
+
+fn write_json_event(output: &mut dyn Write, event: JsonObject) -> NedResult<()> {
+    output.write_all(&format!("{}\n", event.finish()).into_bytes())?;
+    Ok(())
+}
+
 /// Taking into account parameters specifying to display or not display file names and line numbers,
-/// write the filename, and line number if they are given, colored if the parameters specify color,
-/// and with a newline, colon and newline, or colon, also depending on the specified parameters.
+/// write the filename, and line number and column if they are given, colored if the parameters specify
+/// color, and with a newline, colon and newline, or colon, also depending on the specified parameters.
+/// With --null the file name is followed by a NUL rather than by a colon or newline.
 fn write_file_name_and_line_number(
     output: &mut dyn Write,
     parameters: &Parameters,
+    run_state: &RunState,
     file_name: &Option<String>,
     line_number: Option<usize>,
+    position: Option<Position>,
+    context: bool,
 ) -> NedResult<()> {
     if !parameters.quiet {
+        // Context lines are told apart from matching lines, as grep does.
+        let separator = if context { '-' } else { ':' };
         let mut location = "".to_string();
-        if !parameters.no_file_names && !parameters.line_numbers_only {
+        let mut file_name_end = None;
+        if !parameters.no_file_names && !parameters.line_numbers_only && !parameters.heading {
             if let Some(ref file_name) = file_name {
                 location.push_str(&file_name);
+                file_name_end = Some(location.len());
             }
         }
+        // With -w/--whole-files the column and byte offset are after the line number of the
+        // line the match is on, so they aren't taken for a line number and column.
+        let line_number = line_number.or_else(|| position.map(|position| position.line));
         if !parameters.no_line_numbers && !parameters.file_names_only {
             if let Some(line_number) = line_number {
                 if !location.is_empty() {
-                    location.push(':');
+                    location.push(separator);
                 }
                 location.push_str(&line_number.to_string());
             }
         }
+        if let Some(position) = position {
+            if parameters.column || parameters.vimgrep {
+                if !location.is_empty() {
+                    location.push(separator);
+                }
+                location.push_str(&position.column.to_string());
+            }
+            if parameters.byte_offset {
+                if !location.is_empty() {
+                    location.push(separator);
+                }
+                location.push_str(&position.byte_offset.to_string());
+            }
+        }
         if !location.is_empty() {
+            // The file name and numbers, without what follows them, are the hyperlink.
+            let linked_end = location.len();
             location.push_str(
                 if parameters.file_names_only || parameters.line_numbers_only {
                     "\n"
-                } else if parameters.replace.is_some() || parameters.whole_files {
+                } else if (parameters.replace.is_some() || parameters.whole_files)
+                    && !parameters.vimgrep
+                    && !parameters.count
+                    && !parameters.count_matches
+                {
                     ":\n"
+                } else if context {
+                    "-"
                 } else {
                     ":"
                 },
             );
+            if let (true, Some(end)) = (parameters.null, file_name_end) {
+                // The colon, dash, or newline after the file name.
+                location.replace_range(end..end + 1, "\0");
+            }
+            if let (Some(template), Some(path)) =
+                (&parameters.hyperlinks, &run_state.hyperlink_path)
+            {
+                let column = position.map(|position| position.column);
+                location = format!(
+                    "{}{}",
+                    hyperlink(
+                        &url(template, path, line_number, column),
+                        &location[..linked_end]
+                    ),
+                    &location[linked_end..]
+                );
+            }
             if parameters.colors {
                 location = Purple.paint(location).to_string();
             }
@@ -597,6 +1804,123 @@ fn write_file_name_and_line_number(
     Ok(())
 }
 
+/// Write a file's name as the heading of its lines with --heading, after a blank line if it
+/// isn't the first.
+fn write_heading(
+    output: &mut dyn Write,
+    parameters: &Parameters,
+    run_state: &mut RunState,
+    file_name: &Option<String>,
+) -> NedResult<()> {
+    if let Some(file_name) = file_name {
+        if run_state.heading_shown {
+            output.write_all(b"\n")?;
+        }
+        let mut heading = file_name.clone();
+        if let (Some(template), Some(path)) = (&parameters.hyperlinks, &run_state.hyperlink_path) {
+            heading = hyperlink(&url(template, path, None, None), &heading);
+        }
+        heading.push(if parameters.null { '\0' } else { '\n' });
+        if parameters.colors {
+            heading = Purple.paint(heading).to_string();
+        }
+        output.write_all(&heading.into_bytes())?;
+        run_state.heading_shown = true;
+    }
+    Ok(())
+}
+
+/// Write the lines that replacements change, the original lines, after a -, followed by the
+/// replaced lines, after a +, with the file name and the line number of the first original
+/// line before each group of them. The parts of the lines that are different are colored.
+fn write_changes(
+    output: &mut dyn Write,
+    parameters: &Parameters,
+    run_state: &RunState,
+    file_name: &Option<String>,
+    content: &str,
+    new_content: &str,
+) -> NedResult<()> {
+    for change in changes(content, new_content) {
+        write_file_name_and_line_number(
+            output,
+            parameters,
+            run_state,
+            file_name,
+            Some(change.line_number),
+            None,
+            false,
+        )?;
+        let old_lines = change
+            .old_lines
+            .iter()
+            .map(|line| line.trim_end_matches(&['\r', '\n'][..]))
+            .collect::<Vec<&str>>();
+        let new_lines = change
+            .new_lines
+            .iter()
+            .map(|line| line.trim_end_matches(&['\r', '\n'][..]))
+            .collect::<Vec<&str>>();
+        // Lines are compared with the line in the same place in the other group.
+        for (index, old_line) in old_lines.iter().enumerate() {
+            let other_line = new_lines.get(index).unwrap_or(&"");
+            let line = highlight_difference(parameters, old_line, other_line, Red);
+            output.write_all(&format!("-{}\n", line).into_bytes())?;
+        }
+        for (index, new_line) in new_lines.iter().enumerate() {
+            let other_line = old_lines.get(index).unwrap_or(&"");
+            let line = highlight_difference(parameters, new_line, other_line, Green);
+            output.write_all(&format!("+{}\n", line).into_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// Color the part of the line that is different from the other line, what is between what
+/// they have in common at their start and end, if colors are on.
+fn highlight_difference(
+    parameters: &Parameters,
+    line: &str,
+    other_line: &str,
+    colour: Colour,
+) -> String {
+    if !parameters.colors {
+        return line.to_string();
+    }
+    let prefix = line
+        .char_indices()
+        .zip(other_line.chars())
+        .take_while(|&((_, char), other_char)| char == other_char)
+        .last()
+        .map_or(0, |((index, char), _)| index + char.len_utf8());
+    let suffix = line[prefix..]
+        .char_indices()
+        .rev()
+        .zip(other_line[prefix..].chars().rev())
+        .take_while(|&((_, char), other_char)| char == other_char)
+        .last()
+        .map_or(line.len(), |((index, _), _)| prefix + index);
+    format!(
+        "{}{}{}",
+        &line[..prefix],
+        colour.bold().paint(&line[prefix..suffix]),
+        &line[suffix..]
+    )
+}
+
+/// Write the --context-separator between lines that aren't next to each other.
+fn write_context_separator(output: &mut dyn Write, parameters: &Parameters) -> NedResult<()> {
+    let separator = if parameters.colors {
+        Purple
+            .paint(parameters.context_separator.as_str())
+            .to_string()
+    } else {
+        parameters.context_separator.clone()
+    };
+    output.write_all(&format!("{}\n", separator).into_bytes())?;
+    Ok(())
+}
+
 fn write_newline_if_replaced_text_ends_with_newline(
     output: &mut dyn Write,
     text: &str,
@@ -614,33 +1938,53 @@ fn color_matches_with_number_skip_backwards(
     re: &Regex,
     text: &str,
 ) -> (String, bool) {
-    let (new_text, found_matches) = replace(
-        parameters,
-        &re,
-        text,
-        Red.bold().paint("$0").to_string().as_str(),
-    );
-    if parameters.colors {
-        (new_text, found_matches)
-    } else {
-        (text.to_string(), found_matches)
-    }
+    color_matches(parameters, re, text, |index, count| {
+        parameters.include_match(index, count)
+    })
 }
 
 fn color_matches_all(parameters: &Parameters, re: &Regex, text: &str) -> String {
-    if parameters.colors {
-        re.replace_all(&text, Red.bold().paint("$0").to_string().as_str())
-            .into_owned()
-    } else {
-        text.to_string()
+    color_matches(parameters, re, text, |_, _| true).0
+}
+
+/// Color the matches in the text that include includes, given their index and the number of
+/// matches, returning whether there were any.
+fn color_matches(
+    parameters: &Parameters,
+    re: &Regex,
+    text: &str,
+    include: impl Fn(usize, usize) -> bool,
+) -> (String, bool) {
+    let matches = re.find_iter(text).collect::<Vec<Match>>();
+    let count = matches.len();
+    let patterns = parameters.patterns.in_text(text);
+    let mut new_text = String::with_capacity(text.len());
+    let mut found_matches = false;
+    let mut end = 0;
+    for (index, _match) in matches.iter().enumerate() {
+        if include(index, count) {
+            found_matches = true;
+            new_text.push_str(&text[end.._match.start()]);
+            new_text.push_str(&color_match(parameters, &patterns, _match));
+            end = _match.end();
+        }
     }
+    new_text.push_str(&text[end..]);
+    (new_text, found_matches)
 }
 
-/// Color the whole text if --colors has been specified.
-fn color(parameters: &Parameters, text: &str) -> String {
+/// The colours of the matches of each pattern, used in turn if there are more patterns.
+static MATCH_COLOURS: [Colour; 5] = [Red, Blue, Yellow, Cyan, Green];
+
+/// Color a match in the colour of its pattern if --colors has been specified.
+fn color_match(parameters: &Parameters, patterns: &PatternsInText, _match: &Match) -> String {
     if parameters.colors {
-        Red.bold().paint(text).to_string()
+        let index = patterns.index_of(_match.start());
+        MATCH_COLOURS[index % MATCH_COLOURS.len()]
+            .bold()
+            .paint(_match.as_str())
+            .to_string()
     } else {
-        text.to_string()
+        _match.as_str().to_string()
     }
 }
@@ -53,6 +53,9 @@ pub enum NedError {
     Io(io::Error),
     ParameterError(StringError),
     Regex(regex::Error),
+    /// A run that was stopped, or that can't be done, for a reason other than its parameters,
+    /// which is shown without the usage.
+    RunError(StringError),
 }
 
 impl NedError {
@@ -109,6 +112,7 @@ impl fmt::Display for NedError {
             NedError::Io(ref err) => write!(f, "{}", err),
             NedError::ParameterError(ref err) => write!(f, "{}", err),
             NedError::Regex(ref err) => write!(f, "{}", err),
+            NedError::RunError(ref err) => write!(f, "{}", err),
         }
     }
 }
@@ -122,6 +126,7 @@ impl error::Error for NedError {
             NedError::Io(ref err) => Some(err),
             NedError::ParameterError(ref err) => Some(err),
             NedError::Regex(ref err) => Some(err),
+            NedError::RunError(ref err) => Some(err),
         }
     }
 }
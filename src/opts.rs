@@ -70,6 +70,17 @@ pub fn make_opts() -> Options {
          editing.",
         "PATTERN",
     );
+    opts.optmulti(
+        "e",
+        "regexp",
+        "Specify a pattern. It may be given more than once, and with -p, and if it is given \
+         there is no pattern before the files. Lines, or files with -w, match if any of the \
+         patterns match, and each pattern's matches are colored differently. --json and \
+         --format give the index of the pattern of each match, and --sarif has a rule for \
+         each pattern. Groups are numbered across the patterns in order, so the first group \
+         of a pattern after one with two groups is group 3.",
+        "PATTERN",
+    );
     opts.optopt(
         "r",
         "replace",
@@ -108,6 +119,12 @@ pub fn make_opts() -> Options {
          --whole-files.",
     );
     opts.optflag("x", "extended", "Ignore whitespace and # comments.");
+    opts.optflag(
+        "Q",
+        "fixed-strings",
+        "Match the patterns literally, rather than as regular expressions, for paths, URLs, \
+         and code. -x is ignored.",
+    );
     opts.optflag(
         "",
         "case-replacements",
@@ -115,6 +132,12 @@ pub fn make_opts() -> Options {
          lowercase, \\I - initial uppercase (title case), \\F - first uppercase \
          (sentence case) replacements. \\E marks the end of a case replacement.",
     );
+    opts.optflag(
+        "",
+        "literal-replacement",
+        "Replace matches with REPLACEMENT as it is, with $ not referring to groups, for \
+         replacements with shell, PHP, or template variables.",
+    );
     opts.optflag("o", "matches-only", "Show only matches.");
     opts.optopt(
         "g",
@@ -129,7 +152,28 @@ pub fn make_opts() -> Options {
         "Show only filenames containing matches. Use with -v/\
          --no-match to show filenames without matches.",
     );
+    opts.optflag(
+        "",
+        "count",
+        "Show only the number of lines containing matches in each file with matches. Use \
+         with -v/--no-match to show the number of lines without matches. With -r/--replace \
+         show the number of lines that would have replacements, without changing any files.",
+    );
+    opts.optflag(
+        "",
+        "count-matches",
+        "Show only the number of matches in each file with matches. With -r/--replace show \
+         the number of replacements that would be made, without changing any files.",
+    );
     opts.optflag("F", "no-filenames", "Don't show filenames.");
+    opts.optflag(
+        "",
+        "heading",
+        "Show each filename once, before its lines, rather than on each line, with a blank \
+         line between files. Is the default when stdout is a terminal. Use without -w/\
+         --whole-files.",
+    );
+    opts.optflag("", "no-heading", "Show filenames on each line.");
     opts.optflag(
         "l",
         "line-numbers-only",
@@ -142,6 +186,20 @@ pub fn make_opts() -> Options {
         "no-line-numbers",
         "Don't show line numbers. Use without -w/--whole-files.",
     );
+    opts.optflag(
+        "",
+        "column",
+        "Show the column, in characters, of the first match on each line, or in the whole \
+         file with -w/--whole-files, after its line number, which with -w/--whole-files is \
+         that of the line it is on.",
+    );
+    opts.optflag(
+        "",
+        "byte-offset",
+        "Show the offset, in bytes from the start of the file, of the first match on each \
+         line, or in the whole file with -w/--whole-files, after its line number and column, \
+         if it is shown, which with -w/--whole-files is that of the line it is on.",
+    );
     opts.optopt(
         "C",
         "context",
@@ -164,8 +222,29 @@ pub fn make_opts() -> Options {
          --whole-files.",
         "LINES",
     );
+    opts.optopt(
+        "",
+        "context-separator",
+        "Show SEPARATOR between lines that aren't next to each other when showing lines \
+         around matching lines. The default is --. Context lines are shown with a - rather \
+         than a : after their file name and line number.",
+        "SEPARATOR",
+    );
+    opts.optflag(
+        "0",
+        "null",
+        "Follow file names with a NUL rather than a colon or a newline, for xargs -0, and \
+         read the list of files given with --files-from separated by NULs.",
+    );
     opts.optflag("R", "recursive", "Recurse.");
     opts.optflag("l", "follow", "Follow symlinks. (Ignored on Windows.)");
+    opts.optopt(
+        "",
+        "files-from",
+        "Search the files listed in FILE, one per line, or separated by NULs with \
+         -0/--null, as well as any FILEs given, or read the list from stdin if FILE is -.",
+        "FILE",
+    );
     opts.optmulti("", "include", "Match only files that match GLOB.", "GLOB");
     opts.optmulti("", "exclude", "Skip files matching GLOB.", "GLOB");
     opts.optmulti("", "exclude-dir", "Skip directories matching GLOB.", "GLOB");
@@ -192,7 +271,176 @@ pub fn make_opts() -> Options {
         "WHEN",
     );
     opts.optflagopt("", "color", "Synonym for --colors.", "WHEN");
+    opts.optflagopt(
+        "",
+        "hyperlinks",
+        "When showing colors, make filenames and line numbers hyperlinks that terminals \
+         that support them can open, to URLs made from TEMPLATE, in which {path} is the \
+         file's absolute path without its leading /, and {line} and {column} are the line \
+         number and column of the match. 'file:///{path}' if TEMPLATE isn't given. For \
+         example, --hyperlinks='vscode://file/{path}:{line}:{column}'.",
+        "TEMPLATE",
+    );
     opts.optflag("", "stdout", "Output to stdout.");
+    opts.optflag(
+        "",
+        "json",
+        "Output JSON Lines, an object per line for the begin and end of each file with \
+         matches, each line with matches, with their byte offsets, patterns, and groups, each \
+         context line, the replacements in each file, and a summary. -o, -g, -f, and -l are \
+         ignored.",
+    );
+    opts.optflag(
+        "",
+        "sarif",
+        "Output a SARIF 2.1.0 log, for code scanning tools, with a result for each match, with \
+         its file, and the line and column, in characters, of its start and end. -o, -g, -f, \
+         and -l are ignored.",
+    );
+    opts.optmulti(
+        "",
+        "sarif-rule",
+        "The id of the rule in the --sarif log. With more than one pattern, the first is for \
+         the first pattern, the second for the second, and so on. The pattern if RULE isn't \
+         given.",
+        "RULE",
+    );
+    opts.optflag(
+        "",
+        "vimgrep",
+        "Show a line for each match, rather than each line with matches, as \
+         FILE:LINE:COLUMN:TEXT, the way Vim's quickfix list and Emacs' grep-mode read them. \
+         -o, -g, -f, -l, -F, and -L are ignored.",
+    );
+    opts.optopt(
+        "",
+        "format",
+        "Show TEMPLATE for each match, followed by a newline, rather than each line with \
+         matches, with {path}, {line}, {column}, {byte_offset}, {match}, {line_text}, the \
+         index of its pattern, {pattern}, and the groups, {1}, {2}, {name}, etc., replaced \
+         with those of the match. \\n, \\r, \\t, and \
+         \\\\ are replaced with a newline, carriage return, tab, and backslash. -o, -g, -f, \
+         -l, -F, and -L are ignored.",
+        "TEMPLATE",
+    );
+    opts.optflag(
+        "",
+        "stats",
+        "Show the numbers of files walked, skipped, searched, matched, and rewritten, of \
+         errors, of lines and matches found, of replacements made, of bytes read and \
+         written, and the time taken, at the end.",
+    );
+    opts.optflag(
+        "",
+        "interactive",
+        "Ask before making each replacement, showing its file, line, and the \
+         -B/--before and -A/--after lines around it. Answer y to replace, n to not \
+         replace, a to replace it and the rest in the file, or q to stop.",
+    );
+    opts.optflagopt(
+        "",
+        "diff",
+        "Show the changes that replacements would make as a unified diff with LINES lines \
+         of context, 3 if LINES isn't given, without changing any files.",
+        "LINES",
+    );
+    opts.optflag(
+        "",
+        "show-changes",
+        "Show the lines that replacements would change, each original line after a - and \
+         each replaced line after a +, with the filename and line number before them, \
+         without changing any files.",
+    );
+    opts.optflag(
+        "",
+        "patch",
+        "Show the changes that replacements would make as a patch, with a/ and b/ prefixes \
+         on the file names, relative to the current directory, that git apply and patch -p1 \
+         can apply, without changing any files. Use with --diff to give the lines of context.",
+    );
+    opts.optflagopt(
+        "",
+        "backup",
+        "Before replacing, copy each file that will be changed to a backup named with SUFFIX \
+         appended, '~' if SUFFIX isn't given. 'numbered' makes numbered backups, \
+         FILE.~1~, FILE.~2~, etc.",
+        "SUFFIX",
+    );
+    opts.optopt(
+        "",
+        "max-replacements",
+        "Don't change any files if replacing would make more than NUMBER replacements \
+         across all files.",
+        "NUMBER",
+    );
+    opts.optopt(
+        "",
+        "max-files",
+        "Don't change any files if replacing would change more than NUMBER files.",
+        "NUMBER",
+    );
+    opts.optflag(
+        "",
+        "transaction",
+        "Don't change any files unless all files can be read and replaced without errors, \
+         and if one can't be changed, restore those already changed.",
+    );
+    opts.optflag(
+        "",
+        "force-dirty",
+        "Change files in a git work tree that have uncommitted changes. Without it they \
+         aren't changed, and each one that isn't is reported.",
+    );
+    opts.optflag(
+        "",
+        "require-clean",
+        "Don't change any files if any of them have uncommitted changes in a git work \
+         tree.",
+    );
+    opts.optopt(
+        "",
+        "symlinks",
+        "What to do when a file to be changed is a symlink, or is in a symlinked \
+         directory that was walked into with --follow. 'follow' changes the file it links to, 'skip' doesn't change it, and \
+         'replace-link' replaces a symlink with a changed file. The default is 'follow'.",
+        "WHAT",
+    );
+    opts.optopt(
+        "",
+        "hardlinks",
+        "What to do when a file to be changed has other hard links to it. 'break' \
+         replaces it with a changed file leaving the other links unchanged, 'skip' \
+         doesn't change it, and 'edit' changes it in place, so that it is changed for \
+         all of its links, though not atomically. The default is 'break'. (Ignored on \
+         Windows.)",
+        "WHAT",
+    );
+    opts.optflagopt(
+        "",
+        "preserve",
+        "Keep the ATTRIBUTES of each file that is changed, a comma separated list of \
+         'ownership', 'timestamps', or 'all', which is the same as not giving \
+         ATTRIBUTES. Its mode is always kept, and its ownership when it can be. It is \
+         reported if they can't be kept.",
+        "ATTRIBUTES",
+    );
+    opts.optflagopt(
+        "",
+        "journal",
+        "Record the original content of each file that is changed in the journal in DIR, \
+         or if DIR isn't given in ned/journal in $XDG_STATE_HOME, $LOCALAPPDATA, or \
+         ~/.local/state, the first that is set, so that the replace can be undone with \
+         --undo. The run id of the replace is shown when it finishes.",
+        "DIR",
+    );
+    opts.optflagopt(
+        "",
+        "undo",
+        "Restore the files changed by the last replace recorded with --journal, or by \
+         the replace with the RUN id, unless they have changed since. Use with \
+         --journal=DIR if it was recorded in DIR.",
+        "RUN",
+    );
     opts.optflag(
         "q",
         "quiet",
@@ -20,47 +20,87 @@
 
 extern crate regex;
 
+use crate::backup::Backup;
 use crate::colors::Colors;
+use crate::hyperlinks::DEFAULT_TEMPLATE;
+use crate::journal::Journal;
+use crate::links::{Hardlinks, Symlinks};
 use crate::ned_error::{NedError, NedResult, StringError};
 use crate::options_with_defaults::OptionsWithDefaults;
+use crate::patterns::Patterns;
+use crate::preserve::Preserve;
 use glob::Pattern;
 use libc;
 use regex::Regex;
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
 use std::iter::Iterator;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 #[derive(Clone)]
 pub struct Parameters {
     pub all: bool,
+    pub backup: Option<Backup>,
     pub backwards: bool,
+    pub byte_offset: bool,
     pub case_replacements: bool,
     pub colors: bool,
+    pub column: bool,
     pub context_after: usize,
     pub context_before: usize,
+    pub context_separator: String,
+    pub count: bool,
+    pub count_matches: bool,
+    pub diff: Option<usize>,
     pub exclude_dirs: Vec<Pattern>,
     pub excludes: Vec<Pattern>,
     pub file_names_only: bool,
     pub follow: bool,
-    pub globs: Vec<String>,
+    pub format: Option<String>,
+    pub force_dirty: bool,
+    pub globs: Vec<PathBuf>,
     pub group: Option<String>,
+    pub hardlinks: Hardlinks,
+    pub heading: bool,
     pub help: bool,
+    pub hyperlinks: Option<String>,
     pub ignore_non_utf8: bool,
     pub includes: Vec<Pattern>,
+    pub interactive: bool,
+    pub journal: Option<Journal>,
+    pub json: bool,
     pub line_numbers_only: bool,
+    pub literal_replacement: bool,
     pub matches_only: bool,
+    pub max_files: Option<usize>,
+    pub max_replacements: Option<usize>,
     pub no_file_names: bool,
     pub no_line_numbers: bool,
     pub no_match: bool,
+    pub null: bool,
     pub number: Option<usize>,
+    pub patch: bool,
+    pub patterns: Patterns,
+    pub preserve: Preserve,
     pub quiet: bool,
     pub recursive: bool,
     pub regex: Option<Regex>,
     pub replace: Option<String>,
+    pub require_clean: bool,
+    pub sarif: bool,
+    pub sarif_rules: Vec<String>,
+    pub show_changes: bool,
     pub skip: usize,
+    pub stats: bool,
     pub stdin: bool,
     pub stdout: bool,
+    pub symlinks: Symlinks,
+    pub transaction: bool,
+    pub undo: Option<String>,
     pub version: bool,
+    pub vimgrep: bool,
     pub whole_files: bool,
 }
 
@@ -69,6 +109,27 @@ impl Parameters {
         self.skip > 0 || self.number.is_some()
     }
 
+    /// Whether replacements are written to the files, rather than shown, or counted.
+    pub fn writes_files(&self) -> bool {
+        self.replace.is_some()
+            && self.diff.is_none()
+            && !self.show_changes
+            && !self.stdout
+            && !self.count
+            && !self.count_matches
+    }
+
+    /// Whether rewrites are held back until all files have been processed, so that nothing
+    /// is written if there are more changes than --max-files or --max-replacements allow,
+    /// if there are errors with --transaction, or if files have uncommitted changes with
+    /// --require-clean.
+    pub fn stage_rewrites(&self) -> bool {
+        self.transaction
+            || self.require_clean
+            || self.max_files.is_some()
+            || self.max_replacements.is_some()
+    }
+
     pub fn include_match(&self, index: usize, count: usize) -> bool {
         if index >= count || self.skip >= count {
             return false;
@@ -96,7 +157,12 @@ impl Parameters {
     }
 }
 
-pub fn get_parameters(options_with_defaults: &OptionsWithDefaults) -> NedResult<Parameters> {
+/// Get the parameters from the options. isatty is whether the output is a terminal, which is
+/// given rather than checked here so that the tests' output is the same wherever they are run.
+pub fn get_parameters(
+    options_with_defaults: &OptionsWithDefaults,
+    isatty: bool,
+) -> NedResult<Parameters> {
     // -C --context takes precedence over -B --before and -A --after.
     let mut context_before =
         parse_opt_str(&options_with_defaults, "context", Some(0))?.expect("The default is a Some.");
@@ -130,49 +196,156 @@ pub fn get_parameters(options_with_defaults: &OptionsWithDefaults) -> NedResult<
 
     // TODO: Test combinations of file name and line number options.
 
+    // vimgrep always shows file names, line numbers, and columns.
+    let vimgrep = options_with_defaults.opt_present("vimgrep");
+
     // file_names_only takes precedence over line_numbers_only.
-    let file_names_only = options_with_defaults.opt_present("filenames-only");
-    let line_numbers_only =
-        !whole_files && !file_names_only && options_with_defaults.opt_present("line-numbers-only");
+    let file_names_only = !vimgrep && options_with_defaults.opt_present("filenames-only");
+    let line_numbers_only = !vimgrep
+        && !whole_files
+        && !file_names_only
+        && options_with_defaults.opt_present("line-numbers-only");
 
     // file_names_only takes precedence over no_file_names.
-    let no_file_names = !file_names_only && options_with_defaults.opt_present("no-filenames");
-    let no_line_numbers = !line_numbers_only
+    let no_file_names =
+        !vimgrep && !file_names_only && options_with_defaults.opt_present("no-filenames");
+    let no_line_numbers = !vimgrep
+        && !line_numbers_only
         && (file_names_only
             || !whole_files && options_with_defaults.opt_present("no-line-numbers"));
 
-    let regex;
-    let mut globs = options_with_defaults.free();
+    let mut free = options_with_defaults.free();
 
+    // -e --regexp patterns are in addition to the -p --pattern pattern. As with grep, there is
+    // no pattern before the files if there are any.
+    let mut patterns = vec![];
     if options_with_defaults.opt_present("pattern") {
-        let pattern = add_regex_flags_to_pattern(
-            &options_with_defaults,
-            &options_with_defaults.opt_str("pattern").expect(
-                "Bug, already checked that pattern \
-                 is present.",
-            ),
-        );
-        regex = Some(Regex::new(&pattern)?);
-    } else if !options_with_defaults.free().is_empty() {
-        let pattern = globs.remove(0);
-        let pattern = add_regex_flags_to_pattern(&options_with_defaults, &pattern);
-        regex = Some(Regex::new(&pattern)?);
+        patterns.push(options_with_defaults.opt_str("pattern").expect(
+            "Bug, already checked that pattern \
+             is present.",
+        ));
+    } else if !options_with_defaults.opt_present("regexp") && !free.is_empty() {
+        patterns.push(free.remove(0));
+    }
+    patterns.extend(options_with_defaults.opt_strs("regexp"));
+    let fixed_strings = options_with_defaults.opt_present("fixed-strings");
+    let patterns = patterns
+        .iter()
+        .map(|pattern| {
+            if fixed_strings {
+                add_regex_flags_to_pattern(options_with_defaults, &regex::escape(pattern), false)
+            } else {
+                add_regex_flags_to_pattern(options_with_defaults, pattern, true)
+            }
+        })
+        .collect::<Vec<String>>();
+    // Escaped patterns can have whitespace, which -x --extended would ignore.
+    let extended = !fixed_strings && options_with_defaults.opt_present("x");
+    let patterns = Patterns::new(patterns, extended)?;
+    let regex = if patterns.patterns.is_empty() {
+        None
     } else {
-        regex = None;
+        Some(patterns.regex(extended)?)
+    };
+
+    let null = options_with_defaults.opt_present("null");
+    let mut globs = free
+        .into_iter()
+        .map(PathBuf::from)
+        .collect::<Vec<PathBuf>>();
+    let files_from = options_with_defaults.opt_str("files-from");
+    if let Some(ref files_from) = files_from {
+        globs.extend(read_file_list(files_from, null)?);
     }
 
     let number = parse_opt_str(&options_with_defaults, "number", None)?;
     let skip =
         parse_opt_str(&options_with_defaults, "skip", Some(0))?.expect("The default is a Some.");
 
-    let stdin = globs.is_empty();
+    // --undo without RUN undoes the most recent run.
+    let undo = if options_with_defaults.opt_present("undo") {
+        Some(options_with_defaults.opt_str("undo").unwrap_or_default())
+    } else {
+        None
+    };
+    // --undo uses the journal in the directory given by --journal=DIR, or the default one.
+    let journal = if options_with_defaults.opt_present("journal") || undo.is_some() {
+        Some(Journal::new(options_with_defaults.opt_str("journal"))?)
+    } else {
+        None
+    };
+
+    // --diff without LINES shows 3 lines of context, as diff -u does. --patch is a --diff
+    // that git apply and patch -p1 can apply.
+    let patch = options_with_defaults.opt_present("patch");
+    let diff = match options_with_defaults.opt_str("diff") {
+        Some(_) => parse_opt_str(options_with_defaults, "diff", None)?,
+        None if options_with_defaults.opt_present("diff") || patch => Some(3),
+        None => None,
+    };
+
+    let stdin = globs.is_empty() && files_from.is_none();
     let stdout = stdin || options_with_defaults.opt_present("stdout");
+
+    // --require-clean takes precedence over --force-dirty.
+    let require_clean = options_with_defaults.opt_present("require-clean");
+    let force_dirty = !require_clean && options_with_defaults.opt_present("force-dirty");
+
+    let interactive = options_with_defaults.opt_present("interactive");
+    if interactive && stdin {
+        return Err(NedError::ParameterError(StringError {
+            err: "--interactive can't be used when reading from stdin".to_string(),
+        }));
+    }
+    if interactive && files_from.as_deref() == Some("-") {
+        return Err(NedError::ParameterError(StringError {
+            err: "--interactive can't be used when reading the list of files from stdin"
+                .to_string(),
+        }));
+    }
+    let json = options_with_defaults.opt_present("json");
+    let show_changes = options_with_defaults.opt_present("show-changes");
+    if json && (interactive || diff.is_some() || show_changes || vimgrep) {
+        return Err(NedError::ParameterError(StringError {
+            err: "--json can't be used with --interactive, --diff, --patch, --show-changes, or \
+                  --vimgrep"
+                .to_string(),
+        }));
+    }
+
+    // Replacements aren't shown as matches, so their file names would run into them.
+    if vimgrep && options_with_defaults.opt_present("replace") {
+        return Err(NedError::ParameterError(StringError {
+            err: "--vimgrep can't be used with -r/--replace".to_string(),
+        }));
+    }
+
+    let sarif = options_with_defaults.opt_present("sarif");
+    if sarif && (options_with_defaults.opt_present("replace") || json || vimgrep) {
+        return Err(NedError::ParameterError(StringError {
+            err: "--sarif can't be used with -r/--replace, --json, or --vimgrep".to_string(),
+        }));
+    }
+
+    // --json and --sarif take precedence over --count and --count-matches, and with
+    // -v/--no-match --count-matches is the same as --count, there being no non-matches to
+    // count.
+    let no_match = options_with_defaults.opt_present("no-match");
+    let count_matches =
+        !json && !sarif && !no_match && options_with_defaults.opt_present("count-matches");
+    let count = !json
+        && !sarif
+        && (options_with_defaults.opt_present("count")
+            || no_match && options_with_defaults.opt_present("count-matches"));
+    let format = convert_escapes(options_with_defaults.opt_str("format"));
+    if format.is_some() && (json || sarif || vimgrep || no_match || count || count_matches) {
+        return Err(NedError::ParameterError(StringError {
+            err: "--format can't be used with -v/--no-match, --count, --count-matches, --json, \
+                  --sarif, or --vimgrep"
+                .to_string(),
+        }));
+    }
     let replace = convert_escapes(options_with_defaults.opt_str("replace"));
-    // TODO: decide what is the best way to deal with STDOUT_FILENO not being defined in the x86_64-pc-windows-gnu,
-    // x86_64-pc-windows-msvc, or i686-pc-windows-msvc versions of libc.
-    let isatty = unsafe {
-        libc::isatty(/*libc::STDOUT_FILENO as i32*/ 1)
-    } != 0;
 
     let c = options_with_defaults.opt_present("c");
     let mut colors = parse_opt_str(&options_with_defaults, "colors", None)?;
@@ -181,45 +354,159 @@ pub fn get_parameters(options_with_defaults: &OptionsWithDefaults) -> NedResult<
         colors = parse_opt_str(&options_with_defaults, "color", Some(Colors::Off))?;
     }
     let colors = colors.expect("The default is a Some.");
-    let colors = c
-        || (colors == Colors::Always && (replace.is_none() || replace.is_some() && stdout)
-            || colors == Colors::Auto && (replace.is_none() || stdout) && isatty)
-            && colors != Colors::Never;
+    // Replacements are shown, rather than written to files, with --stdout, --diff,
+    // --show-changes, and the counts.
+    let shows_replacements = stdout || diff.is_some() || show_changes || count || count_matches;
+    // JSON, SARIF, and patches are never colored.
+    let colors = !json
+        && !sarif
+        && !patch
+        && (c
+            || (colors == Colors::Always && (replace.is_none() || shows_replacements)
+                || colors == Colors::Auto && (replace.is_none() || shows_replacements) && isatty)
+                && colors != Colors::Never);
+    // Hyperlinks are only shown with colors, which are only shown for terminals.
+    let hyperlinks = if colors && options_with_defaults.opt_present("hyperlinks") {
+        Some(
+            options_with_defaults
+                .opt_str("hyperlinks")
+                .unwrap_or_else(|| DEFAULT_TEMPLATE.to_string()),
+        )
+    } else {
+        None
+    };
+
+    // --no-heading takes precedence over --heading. Headings are only shown for the lines
+    // of files, not for whole files, replacements, or the other ways of showing matches.
+    let heading = !options_with_defaults.opt_present("no-heading")
+        && (options_with_defaults.opt_present("heading") || isatty)
+        && !whole_files
+        && replace.is_none()
+        && !json
+        && !sarif
+        && !vimgrep
+        && !count
+        && !count_matches
+        && !file_names_only
+        && !line_numbers_only
+        && !no_file_names;
 
     Ok(Parameters {
         all: options_with_defaults.opt_present("all"),
+        backup: parse_opt_str(options_with_defaults, "backup", None)?,
         backwards: options_with_defaults.opt_present("backwards"),
+        byte_offset: options_with_defaults.opt_present("byte-offset"),
         case_replacements: options_with_defaults.opt_present("case-replacements"),
         colors,
+        column: options_with_defaults.opt_present("column"),
         context_after,
         context_before,
+        context_separator: convert_escapes(options_with_defaults.opt_str("context-separator"))
+            .unwrap_or_else(|| "--".to_string()),
+        count,
+        count_matches,
+        diff,
         exclude_dirs,
         excludes,
         file_names_only,
         follow: options_with_defaults.opt_present("follow"),
+        format,
+        force_dirty,
         globs,
         group: options_with_defaults.opt_str("group"),
+        hardlinks: parse_opt_str(options_with_defaults, "hardlinks", Some(Hardlinks::Break))?
+            .expect("The default is a Some."),
+        heading,
         help: options_with_defaults.opt_present("help"),
+        hyperlinks,
         ignore_non_utf8: options_with_defaults.opt_present("ignore-non-utf8"),
         includes,
+        interactive,
+        journal,
+        json,
         line_numbers_only,
+        literal_replacement: options_with_defaults.opt_present("literal-replacement"),
         matches_only: options_with_defaults.opt_present("matches-only"),
+        max_files: parse_opt_str(options_with_defaults, "max-files", None)?,
+        max_replacements: parse_opt_str(options_with_defaults, "max-replacements", None)?,
         no_file_names,
         no_line_numbers,
-        no_match: options_with_defaults.opt_present("no-match"),
+        no_match,
+        null,
         number,
+        patch,
+        patterns,
+        preserve: parse_opt_str(options_with_defaults, "preserve", Some(Preserve::default()))?
+            .expect("The default is a Some."),
         quiet: options_with_defaults.opt_present("quiet"),
         recursive: options_with_defaults.opt_present("recursive"),
         regex,
         replace,
+        require_clean,
+        sarif,
+        sarif_rules: options_with_defaults.opt_strs("sarif-rule"),
+        show_changes,
         skip,
+        stats: options_with_defaults.opt_present("stats"),
         stdin,
         stdout,
+        symlinks: parse_opt_str(options_with_defaults, "symlinks", Some(Symlinks::Follow))?
+            .expect("The default is a Some."),
+        transaction: options_with_defaults.opt_present("transaction"),
+        undo,
         version: options_with_defaults.opt_present("version"),
+        vimgrep,
         whole_files,
     })
 }
 
+/// Whether stdout is a terminal.
+pub fn stdout_is_terminal() -> bool {
+    // TODO: decide what is the best way to deal with STDOUT_FILENO not being defined in the x86_64-pc-windows-gnu,
+    // x86_64-pc-windows-msvc, or i686-pc-windows-msvc versions of libc.
+    let isatty = unsafe {
+        libc::isatty(/*libc::STDOUT_FILENO as i32*/ 1)
+    };
+    isatty != 0
+}
+
+/// Read a list of files, one per line, or separated by NULs with --null, from a file, or from
+/// stdin if it is -.
+fn read_file_list(files_from: &str, null: bool) -> NedResult<Vec<PathBuf>> {
+    let mut bytes = vec![];
+    if files_from == "-" {
+        io::stdin().read_to_end(&mut bytes)?;
+    } else {
+        File::open(files_from)?.read_to_end(&mut bytes)?;
+    }
+    let separator = if null { b'\0' } else { b'\n' };
+    bytes
+        .split(|byte| *byte == separator)
+        .map(|file| {
+            if null {
+                file
+            } else {
+                file.strip_suffix(b"\r").unwrap_or(file)
+            }
+        })
+        .filter(|file| !file.is_empty())
+        .map(path_from_bytes)
+        .collect()
+}
+
+/// File names can be any bytes but / and NUL on Unix, whether they are UTF-8 or not.
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> NedResult<PathBuf> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    Ok(PathBuf::from(OsStr::from_bytes(bytes)))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> NedResult<PathBuf> {
+    Ok(PathBuf::from(String::from_utf8(bytes.to_vec())?))
+}
+
 fn convert_escapes(str: Option<String>) -> Option<String> {
     match str {
         Some(str) => {
@@ -261,10 +548,11 @@ fn convert_escapes(str: Option<String>) -> Option<String> {
 fn add_regex_flags_to_pattern(
     options_with_defaults: &OptionsWithDefaults,
     pattern: &str,
+    extended: bool,
 ) -> String {
     let mut regex_flags = "".to_string();
     for option in &["i", "s", "m", "x"] {
-        if options_with_defaults.opt_present(&option) {
+        if options_with_defaults.opt_present(&option) && (extended || *option != "x") {
             regex_flags.push_str(&option);
         }
     }
@@ -0,0 +1,143 @@
+//
+// ned, https://github.com/nevdelap/ned, patterns.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use crate::ned_error::NedResult;
+use regex::{Regex, RegexSet};
+use std::cell::OnceCell;
+
+/// The patterns given with -p/--pattern, or before the files, and with -e/--regexp. A line
+/// matches if any of them match, and each match is of the first of them that matches there.
+#[derive(Clone, Debug)]
+pub struct Patterns {
+    /// The patterns, with the regex flags added.
+    pub patterns: Vec<String>,
+    set: RegexSet,
+    /// All of the patterns, each in a group, to tell which of them a match is of.
+    which: Regex,
+    /// The number of the group around each pattern in which.
+    groups: Vec<usize>,
+}
+
+impl Default for Patterns {
+    fn default() -> Patterns {
+        Patterns {
+            patterns: vec![],
+            set: RegexSet::empty(),
+            which: Regex::new("").expect("Bug, the empty pattern is valid."),
+            groups: vec![],
+        }
+    }
+}
+
+impl Patterns {
+    /// extended is whether the patterns have the x flag, with which comments run to the end of
+    /// the line.
+    pub fn new(patterns: Vec<String>, extended: bool) -> NedResult<Patterns> {
+        let set = RegexSet::new(&patterns)?;
+        let mut groups = vec![];
+        let mut group = 1;
+        for pattern in &patterns {
+            groups.push(group);
+            group += Regex::new(pattern)?.captures_len();
+        }
+        let which = Regex::new(&alternatives(&patterns, "(", extended))?;
+        Ok(Patterns {
+            patterns,
+            set,
+            which,
+            groups,
+        })
+    }
+
+    /// The regex that matches any of the patterns. With one pattern it is that pattern, so that
+    /// its groups are numbered as they are in it.
+    pub fn regex(&self, extended: bool) -> NedResult<Regex> {
+        Ok(if self.patterns.len() == 1 {
+            Regex::new(&self.patterns[0])?
+        } else {
+            Regex::new(&alternatives(&self.patterns, "(?:", extended))?
+        })
+    }
+
+    /// Which of the patterns the matches of the regex in the text are of.
+    pub fn in_text<'a>(&'a self, text: &'a str) -> PatternsInText<'a> {
+        PatternsInText {
+            patterns: self,
+            text,
+            only: OnceCell::new(),
+        }
+    }
+}
+
+/// Which of the patterns the matches of the regex in a text are of.
+pub struct PatternsInText<'a> {
+    patterns: &'a Patterns,
+    text: &'a str,
+    /// The only pattern that matches anywhere in the text, if only one does, which is found
+    /// once for all of its matches.
+    only: OnceCell<Option<usize>>,
+}
+
+impl<'a> PatternsInText<'a> {
+    /// The index of the pattern that the match of the regex starting at start is of.
+    pub fn index_of(&self, start: usize) -> usize {
+        let patterns = self.patterns;
+        if patterns.patterns.len() < 2 {
+            return 0;
+        }
+        // Most often only one of the patterns matches in a text, which all of its matches are
+        // then of.
+        let only = self.only.get_or_init(|| {
+            let matching = patterns.set.matches(self.text);
+            if matching.iter().count() == 1 {
+                matching.iter().next()
+            } else {
+                None
+            }
+        });
+        if let Some(index) = *only {
+            return index;
+        }
+        let mut locations = patterns.which.capture_locations();
+        if patterns
+            .which
+            .captures_read_at(&mut locations, self.text, start)
+            .is_some()
+        {
+            for (index, &group) in patterns.groups.iter().enumerate() {
+                if locations.get(group).is_some() {
+                    return index;
+                }
+            }
+        }
+        0
+    }
+}
+
+/// The patterns as alternatives, each in a group opened with open. With the x flag each
+/// pattern is followed by a newline so that a comment at its end doesn't run into the next.
+fn alternatives(patterns: &[String], open: &str, extended: bool) -> String {
+    let end = if extended { "\n)" } else { ")" };
+    patterns
+        .iter()
+        .map(|pattern| format!("{}{}{}", open, pattern, end))
+        .collect::<Vec<String>>()
+        .join("|")
+}
@@ -0,0 +1,62 @@
+//
+// ned, https://github.com/nevdelap/ned, preserve.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use crate::ned_error::StringError;
+use std::str::FromStr;
+
+/// The attributes of a file, other than its mode which is always kept, that are kept when it
+/// is replaced.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Preserve {
+    pub ownership: bool,
+    pub timestamps: bool,
+}
+
+impl FromStr for Preserve {
+    type Err = StringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Preserve {
+                ownership: true,
+                timestamps: true,
+            });
+        }
+        let mut preserve = Preserve::default();
+        for attribute in s.split(',') {
+            match attribute.trim() {
+                // Accepted as cp accepts it, the mode is kept anyway.
+                "mode" => {}
+                "ownership" => preserve.ownership = true,
+                "timestamps" => preserve.timestamps = true,
+                "all" => {
+                    preserve.ownership = true;
+                    preserve.timestamps = true;
+                }
+                _ => {
+                    return Err(StringError {
+                        err: format!("invalid preserve attribute {}", attribute),
+                    })
+                }
+            }
+        }
+        Ok(preserve)
+    }
+}
@@ -0,0 +1,308 @@
+//
+// ned, https://github.com/nevdelap/ned, rewrite.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use crate::links::{Hardlinks, Symlinks};
+use crate::ned_error::{stderr_write_file_err, NedResult, StringError};
+use crate::parameters::Parameters;
+use crate::paths::absolute_path;
+use crate::preserve::Preserve;
+use std::fs::{self, File, FileTimes, Metadata, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Replace the content of a file with the replacements made in it, deciding what to do if it
+/// is a symlink, or is in a symlinked directory below walk_root, the file or directory it was
+/// found in, or is linked to by other hard links, as specified by --symlinks and
+/// --hardlinks, and reporting the decision. If --backup was specified the original is first
+/// copied to its backup, and if --journal was specified it is recorded in the journal. Its
+/// attributes are kept as specified by --preserve. Returns the path of the file written, or
+/// None if it wasn't changed.
+pub fn rewrite_file(
+    parameters: &Parameters,
+    path: &Path,
+    walk_root: &Path,
+    bytes: &[u8],
+) -> NedResult<Option<PathBuf>> {
+    let is_symlink = fs::symlink_metadata(path)?.file_type().is_symlink();
+    let path = if is_symlink || in_symlinked_dir(path, walk_root) {
+        let what = if is_symlink {
+            "is a symlink"
+        } else {
+            "is in a symlinked directory"
+        };
+        match parameters.symlinks {
+            Symlinks::Skip => {
+                report_links(path, &format!("{}, not changing it", what));
+                return Ok(None);
+            }
+            Symlinks::ReplaceLink if is_symlink => {
+                report_links(path, &format!("{}, replacing it with a file", what));
+                // Canonicalizing the symlink would give the file it links to.
+                let parent = match path.parent() {
+                    Some(parent) if !parent.as_os_str().is_empty() => parent,
+                    _ => Path::new("."),
+                };
+                let path = fs::canonicalize(parent)?.join(
+                    path.file_name()
+                        .expect("Bug, a file being replaced has a file name."),
+                );
+                // The new file gets the permissions of the file that was linked to.
+                back_up_and_write(parameters, &path, bytes, write_file_atomically)?;
+                return Ok(Some(path));
+            }
+            Symlinks::ReplaceLink => {
+                report_links(
+                    path,
+                    &format!("{}, which can't be replaced, not changing it", what),
+                );
+                return Ok(None);
+            }
+            Symlinks::Follow => {
+                let target = fs::canonicalize(path)?;
+                report_links(path, &format!("{}, changing {}", what, target.display()));
+                target
+            }
+        }
+    } else {
+        fs::canonicalize(path)?
+    };
+
+    let links = link_count(&fs::metadata(&path)?);
+    if links > 1 {
+        match parameters.hardlinks {
+            Hardlinks::Skip => {
+                report_links(&path, &format!("has {} links, not changing it", links));
+                return Ok(None);
+            }
+            Hardlinks::Edit => {
+                report_links(&path, &format!("has {} links, changing all of them", links));
+                back_up_and_write(parameters, &path, bytes, write_file_in_place)?;
+                return Ok(Some(path));
+            }
+            Hardlinks::Break => {
+                report_links(
+                    &path,
+                    &format!("has {} links, only changing this one", links),
+                );
+            }
+        }
+    }
+    back_up_and_write(parameters, &path, bytes, write_file_atomically)?;
+    Ok(Some(path))
+}
+
+/// Write a file with write, after backing it up, and keeping its original content in the
+/// journal. It is only recorded in the journal as replaced once it has been written, so that
+/// --undo doesn't take a file that failed to be written for one that has changed since.
+fn back_up_and_write(
+    parameters: &Parameters,
+    path: &Path,
+    bytes: &[u8],
+    write: fn(&Path, &[u8], &Preserve) -> NedResult<()>,
+) -> NedResult<()> {
+    if let Some(ref backup) = parameters.backup {
+        backup.back_up(path)?;
+    }
+    if let Some(ref journal) = parameters.journal {
+        journal.keep_original(path)?;
+    }
+    if let Err(err) = write(path, bytes, &parameters.preserve) {
+        if let Some(ref journal) = parameters.journal {
+            journal.forget_original(path);
+        }
+        return Err(err);
+    }
+    if let Some(ref journal) = parameters.journal {
+        journal.record(path, bytes)?;
+    }
+    Ok(())
+}
+
+/// Whether any of the directories that the file was found in below walk_root is a symlink.
+/// The directories of walk_root itself were named, not walked into, so they don't count, and
+/// the paths are made absolute so that it doesn't matter how the file was named.
+fn in_symlinked_dir(path: &Path, walk_root: &Path) -> bool {
+    let path = absolute_path(path);
+    let walk_root = absolute_path(walk_root);
+    path.ancestors()
+        .skip(1)
+        .take_while(|ancestor| *ancestor != walk_root && ancestor.starts_with(&walk_root))
+        .any(|ancestor| {
+            fs::symlink_metadata(ancestor)
+                .map(|metadata| metadata.file_type().is_symlink())
+                .unwrap_or(false)
+        })
+}
+
+#[cfg(unix)]
+fn link_count(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.nlink()
+}
+
+// The number of links isn't available on other platforms in stable Rust.
+#[cfg(not(unix))]
+fn link_count(_metadata: &Metadata) -> u64 {
+    1
+}
+
+fn report_links(path: &Path, decision: &str) {
+    stderr_write_file_err(
+        path,
+        &StringError {
+            err: decision.to_string(),
+        },
+    );
+}
+
+/// Write a file by overwriting its content, so that it is seen through all of its hard links.
+/// Unlike write_file_atomically() it can be left partially written.
+fn write_file_in_place(path: &Path, bytes: &[u8], preserve: &Preserve) -> NedResult<()> {
+    let metadata = fs::metadata(path)?;
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    file.write_all(bytes)?;
+    file.set_len(bytes.len() as u64)?;
+    keep_attributes(path, &file, &metadata, preserve)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Write a file without ever leaving it partially written. The content is written to a
+/// temporary file in the same directory, synced to disk, given the permissions of the file
+/// it replaces, if there is one, and its other attributes as specified by preserve, and then
+/// renamed over it.
+pub fn write_file_atomically(path: &Path, bytes: &[u8], preserve: &Preserve) -> NedResult<()> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => Some(metadata),
+        Err(ref err) if err.kind() == ErrorKind::NotFound => None,
+        Err(err) => return Err(err.into()),
+    };
+    let (temp_path, mut temp_file) = create_temp_file(path)?;
+    let result = temp_file
+        .write_all(bytes)
+        .and_then(|_| match metadata {
+            Some(ref metadata) => keep_attributes(path, &temp_file, metadata, preserve),
+            None => Ok(()),
+        })
+        .and_then(|_| temp_file.sync_all())
+        .and_then(|_| fs::rename(&temp_path, path));
+    if let Err(err) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err.into());
+    }
+    sync_parent_dir(path);
+    Ok(())
+}
+
+/// Give the new file the original's permissions, which is an error if it can't be done, its
+/// ownership if it can be, and its timestamps if they are to be preserved. Ownership and
+/// timestamps not being kept is reported if they are to be preserved, since it can need
+/// privileges that the user doesn't have.
+fn keep_attributes(
+    path: &Path,
+    file: &File,
+    metadata: &Metadata,
+    preserve: &Preserve,
+) -> io::Result<()> {
+    // Ownership first because changing it can clear the setuid and setgid bits. It is always
+    // kept when it can be, as it is by editing a file in place.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{fchown, MetadataExt};
+        if let Err(err) = fchown(file, Some(metadata.uid()), Some(metadata.gid())) {
+            // Giving a file away needs privileges that keeping its group may not.
+            let group_kept = fchown(file, None, Some(metadata.gid())).is_ok();
+            if preserve.ownership {
+                let what = if group_kept {
+                    "owner"
+                } else {
+                    "owner and group"
+                };
+                report_not_preserved(path, what, &err);
+            }
+        }
+    }
+    file.set_permissions(metadata.permissions())?;
+    if preserve.timestamps {
+        let mut times = FileTimes::new();
+        if let Ok(accessed) = metadata.accessed() {
+            times = times.set_accessed(accessed);
+        }
+        if let Ok(modified) = metadata.modified() {
+            times = times.set_modified(modified);
+        }
+        if let Err(err) = file.set_times(times) {
+            report_not_preserved(path, "timestamps", &err);
+        }
+    }
+    Ok(())
+}
+
+fn report_not_preserved(path: &Path, what: &str, err: &io::Error) {
+    stderr_write_file_err(
+        path,
+        &StringError {
+            err: format!("couldn't keep its {}, {}", what, err),
+        },
+    );
+}
+
+/// Create a new hidden file next to the file being written, named so that it is recognisable if it is
+/// ever left behind, and so that it is skipped by ned unless -a/--all is specified.
+fn create_temp_file(path: &Path) -> io::Result<(PathBuf, File)> {
+    let file_name = path
+        .file_name()
+        .expect("Bug, a file being written has a file name.")
+        .to_string_lossy();
+    let mut attempt = 0;
+    loop {
+        let temp_path = path.with_file_name(format!(
+            ".{}.ned-{}-{}.tmp",
+            file_name,
+            process::id(),
+            attempt
+        ));
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)
+        {
+            Ok(file) => return Ok((temp_path, file)),
+            Err(ref err) if err.kind() == ErrorKind::AlreadyExists && attempt < 100 => {
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Make the rename durable. Not all platforms and file systems allow syncing a directory, so
+/// this is best effort.
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Ok(dir) = File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
@@ -0,0 +1,70 @@
+//
+// ned, https://github.com/nevdelap/ned, run_state.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use crate::git::GitStatus;
+use crate::stats::Stats;
+use std::io::BufRead;
+use std::path::PathBuf;
+
+/// What changes as files are processed, as opposed to the Parameters, which don't.
+pub struct RunState {
+    /// Set when lines have been shown around matching lines, so that the --context-separator
+    /// is shown before the next ones that aren't next to them, even in the next file.
+    pub context_shown: bool,
+    /// Which files have uncommitted changes in git.
+    pub git_status: GitStatus,
+    /// Set when a file's --heading has been shown, so that the next one is separated from it.
+    pub heading_shown: bool,
+    /// The absolute path of the file being processed, encoded for --hyperlinks URLs, looked
+    /// up once for all of its lines, or None if there isn't one.
+    pub hyperlink_path: Option<String>,
+    /// Where answers to --interactive questions are read from.
+    pub input: Box<dyn BufRead>,
+    /// Set when q is answered to an --interactive question, to stop processing files.
+    pub quit: bool,
+    /// The files to be rewritten, the walk roots they were found in, and their new content,
+    /// when they are held back.
+    pub rewrites: Vec<(PathBuf, PathBuf, Vec<u8>)>,
+    /// The --sarif results, written at the end.
+    pub sarif_results: Vec<String>,
+    pub stats: Stats,
+    /// Files with matches that have uncommitted changes, with --require-clean.
+    pub uncommitted_files: usize,
+    /// The file or directory given that the files being processed were found in.
+    pub walk_root: PathBuf,
+}
+
+impl RunState {
+    pub fn new(input: Box<dyn BufRead>) -> RunState {
+        RunState {
+            context_shown: false,
+            git_status: GitStatus::default(),
+            heading_shown: false,
+            hyperlink_path: None,
+            input,
+            quit: false,
+            rewrites: vec![],
+            sarif_results: vec![],
+            stats: Stats::default(),
+            uncommitted_files: 0,
+            walk_root: PathBuf::new(),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//
+// ned, https://github.com/nevdelap/ned, sarif.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use crate::json::{array, JsonObject};
+
+/// Where a match starts or ends, as its 1 based line number and column, in characters.
+pub type LineColumn = (usize, usize);
+
+/// A SARIF result for a match, for --sarif, of the rule at rule_index in the log. The end is
+/// the line and column just after it.
+pub fn result(
+    rule_id: &str,
+    rule_index: usize,
+    uri: &str,
+    start: LineColumn,
+    end: LineColumn,
+    text: &str,
+) -> String {
+    let region = JsonObject::new()
+        .number("startLine", start.0)
+        .number("startColumn", start.1)
+        .number("endLine", end.0)
+        .number("endColumn", end.1)
+        .value("snippet", &JsonObject::new().string("text", text).finish());
+    let physical_location = JsonObject::new()
+        .value(
+            "artifactLocation",
+            &JsonObject::new().string("uri", uri).finish(),
+        )
+        .value("region", &region.finish());
+    let location = JsonObject::new().value("physicalLocation", &physical_location.finish());
+    JsonObject::new()
+        .string("ruleId", rule_id)
+        .number("ruleIndex", rule_index)
+        .string("level", "warning")
+        .value(
+            "message",
+            &JsonObject::new()
+                .string("text", &format!("Matches {}: {}", rule_id, text))
+                .finish(),
+        )
+        .value("locations", &array(&[location.finish()]))
+        .finish()
+}
+
+/// A SARIF 2.1.0 log of a run of ned, with a rule for each pattern, given as its id and the
+/// pattern, and its results. Columns are in characters, rather than SARIF's default UTF-16 code
+/// units.
+pub fn log(rules: &[(String, &str)], results: &[String]) -> String {
+    let rules = rules
+        .iter()
+        .map(|(rule_id, pattern)| {
+            JsonObject::new()
+                .string("id", rule_id)
+                .value(
+                    "shortDescription",
+                    &JsonObject::new()
+                        .string("text", &format!("Matches of {}", pattern))
+                        .finish(),
+                )
+                .finish()
+        })
+        .collect::<Vec<String>>();
+    let driver = JsonObject::new()
+        .string("name", "ned")
+        .string("version", env!("CARGO_PKG_VERSION"))
+        .string("informationUri", "https://github.com/nevdelap/ned")
+        .value("rules", &array(&rules));
+    let run = JsonObject::new()
+        .value(
+            "tool",
+            &JsonObject::new().value("driver", &driver.finish()).finish(),
+        )
+        .string("columnKind", "unicodeCodePoints")
+        .value("results", &array(results));
+    JsonObject::new()
+        .string("version", "2.1.0")
+        .string("$schema", "https://json.schemastore.org/sarif-2.1.0.json")
+        .value("runs", &array(&[run.finish()]))
+        .finish()
+}
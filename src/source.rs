@@ -22,10 +22,11 @@ use std::fs::File;
 #[cfg(test)]
 use std::io::Cursor;
 use std::io::Read;
+use std::path::PathBuf;
 
 pub enum Source {
     Stdin(Box<dyn Read>),
-    File(Box<File>),
+    File(Box<File>, PathBuf),
     #[cfg(test)]
     Cursor(Box<Cursor<Vec<u8>>>),
 }
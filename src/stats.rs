@@ -0,0 +1,130 @@
+//
+// ned, https://github.com/nevdelap/ned, stats.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use crate::json::JsonObject;
+use crate::ned_error::NedResult;
+use std::io::Write;
+use std::time::Instant;
+
+/// What was done in a run, for --stats and the --json summary.
+pub struct Stats {
+    /// Files found in the directories walked, whether they were skipped or not.
+    pub files_walked: usize,
+    pub files_skipped_hidden: usize,
+    /// Files skipped because of --include or --exclude.
+    pub files_skipped_excluded: usize,
+    /// Files skipped with -u/--ignore-non-utf8.
+    pub files_skipped_non_utf8: usize,
+    /// Files and directories that couldn't be walked to, read, or processed, which have been
+    /// reported.
+    pub errors: usize,
+    pub files_searched: usize,
+    pub files_matched: usize,
+    pub lines_matched: usize,
+    pub matches: usize,
+    pub replacements: usize,
+    pub files_rewritten: usize,
+    pub bytes_read: usize,
+    pub bytes_written: usize,
+    start: Instant,
+}
+
+impl Default for Stats {
+    fn default() -> Stats {
+        Stats {
+            files_walked: 0,
+            files_skipped_hidden: 0,
+            files_skipped_excluded: 0,
+            files_skipped_non_utf8: 0,
+            errors: 0,
+            files_searched: 0,
+            files_matched: 0,
+            lines_matched: 0,
+            matches: 0,
+            replacements: 0,
+            files_rewritten: 0,
+            bytes_read: 0,
+            bytes_written: 0,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Stats {
+    pub fn write(&self, output: &mut dyn Write) -> NedResult<()> {
+        let files_skipped =
+            self.files_skipped_hidden + self.files_skipped_excluded + self.files_skipped_non_utf8;
+        let stats = format!(
+            "\n{} files walked\n\
+             {} files skipped, {} hidden, {} excluded, {} not UTF-8\n\
+             {} errors\n\
+             {} files searched\n\
+             {} files matched\n\
+             {} lines matched\n\
+             {} matches\n\
+             {} replacements\n\
+             {} files rewritten\n\
+             {} bytes read\n\
+             {} bytes written\n\
+             {:.3} seconds\n",
+            self.files_walked,
+            files_skipped,
+            self.files_skipped_hidden,
+            self.files_skipped_excluded,
+            self.files_skipped_non_utf8,
+            self.errors,
+            self.files_searched,
+            self.files_matched,
+            self.lines_matched,
+            self.matches,
+            self.replacements,
+            self.files_rewritten,
+            self.bytes_read,
+            self.bytes_written,
+            self.start.elapsed().as_secs_f64()
+        );
+        output.write_all(&stats.into_bytes())?;
+        Ok(())
+    }
+
+    /// Add the stats to a JSON object. files and files_with_matches are the same as
+    /// files_searched and files_matched, and are kept for the summary's original keys.
+    pub fn add_to_json(&self, json: JsonObject) -> JsonObject {
+        json.number("files", self.files_searched)
+            .number("files_with_matches", self.files_matched)
+            .number("matches", self.matches)
+            .number("replacements", self.replacements)
+            .number("files_walked", self.files_walked)
+            .number("files_skipped_hidden", self.files_skipped_hidden)
+            .number("files_skipped_excluded", self.files_skipped_excluded)
+            .number("files_skipped_non_utf8", self.files_skipped_non_utf8)
+            .number("errors", self.errors)
+            .number("files_searched", self.files_searched)
+            .number("files_matched", self.files_matched)
+            .number("lines_matched", self.lines_matched)
+            .number("files_rewritten", self.files_rewritten)
+            .number("bytes_read", self.bytes_read)
+            .number("bytes_written", self.bytes_written)
+            .value(
+                "elapsed_seconds",
+                &format!("{:.3}", self.start.elapsed().as_secs_f64()),
+            )
+    }
+}
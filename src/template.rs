@@ -0,0 +1,46 @@
+//
+// ned, https://github.com/nevdelap/ned, template.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+/// Render a --format template, replacing each {placeholder} with its value. Placeholders
+/// without a value, and braces that aren't around a placeholder, are left as they are.
+pub fn render(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let after_open = &rest[open + 1..];
+        match after_open.find(&['{', '}'][..]) {
+            Some(close) if after_open[close..].starts_with('}') => {
+                let placeholder = &after_open[..close];
+                match value(placeholder) {
+                    Some(value) => rendered.push_str(&value),
+                    None => rendered.push_str(&rest[open..open + close + 2]),
+                }
+                rest = &after_open[close + 1..];
+            }
+            _ => {
+                rendered.push('{');
+                rest = after_open;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
@@ -202,7 +202,7 @@ fn test(args: &str, expected_file_names: &[&str]) {
         .collect::<Vec<String>>();
     env::set_var("NED_DEFAULTS", "");
     let options_with_defaults = OptionsWithDefaults::new(make_opts(), &args).unwrap();
-    let parameters = get_parameters(&options_with_defaults).unwrap();
+    let parameters = get_parameters(&options_with_defaults, false).unwrap();
     let paths = Files::new(&parameters, &parameters.globs[0]);
     let mut file_names = paths
         .map(|path| path.file_name().unwrap().to_str().unwrap().to_string())
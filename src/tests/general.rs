@@ -58,6 +58,37 @@ fn basic_replace() {
     test(&args, expected_exit_code, &expected_screen_output);
 }
 
+#[test]
+fn basic_match_multiple_patterns() {
+    let args = vec!["-e", "ghastly", "-e", "widow", "test/file1.txt"];
+    let expected_exit_code = 0;
+    let expected_screen_output = [
+        "test/file1.txt:1:The accidentally ghastly hand plans AN ESCAPE \
+                                   from a cream puff the placid widow. A slovenly\n",
+    ];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+}
+
+#[test]
+fn replace_multiple_patterns_groups() {
+    let args = vec![
+        "--stdout",
+        "-e",
+        "(acc)identally",
+        "-e",
+        "(gh)(ast)ly",
+        "test/file1.txt",
+        "--replace",
+        "[$1$2$3]",
+    ];
+    let expected_exit_code = 0;
+    let expected_screen_output = ["test/file1.txt:\nThe [acc] [ghast] hand plans AN \
+                                   ESCAPE from a cream puff the placid widow. A slovenly\n"];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+}
+
 #[test]
 fn escape_replace_embedded_real_newline_carriage_return_tab_backslash() {
     let args = vec![
@@ -372,6 +403,90 @@ fn basic_match_file_names_only_whole_files() {
     test(&args, expected_exit_code, &expected_screen_output);
 }
 
+#[test]
+fn basic_match_file_names_only_null() {
+    let args = vec!["accidentally", "test", "--filenames-only", "--null"];
+    let expected_exit_code = 0;
+    let expected_screen_output = ["test/file1.txt\0"];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+}
+
+#[test]
+fn basic_match_null() {
+    let args = vec!["accidentally", "test", "-0"];
+    let expected_exit_code = 0;
+    let expected_screen_output = [
+        "test/file1.txt\x001:The accidentally ghastly hand plans AN \
+                                   ESCAPE from a cream puff the placid widow. A slovenly\n",
+    ];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+}
+
+#[test]
+fn basic_match_files_from_null() {
+    let files_from =
+        std::env::temp_dir().join(format!("ned_test_files_from_{}", std::process::id()));
+    std::fs::write(&files_from, "test/file1.txt\0test/dir1/file2.txt\0").unwrap();
+    let args = vec![
+        "accidentally",
+        "--files-from",
+        files_from.to_str().unwrap(),
+        "-0",
+        "-f",
+    ];
+    let expected_exit_code = 0;
+    let expected_screen_output = ["test/file1.txt\0"];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+    let _ = std::fs::remove_file(&files_from);
+}
+
+#[cfg(unix)]
+#[test]
+fn basic_match_files_from_with_non_utf8_name() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = std::env::temp_dir().join(format!("ned_test_non_utf8_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join(OsStr::from_bytes(b"dog\xff.txt"));
+    std::fs::write(&file, "the dog barked\n").unwrap();
+    let files_from = dir.join("files");
+    let mut list = file.as_os_str().as_bytes().to_vec();
+    list.extend(b"\ntest/file1.txt\n");
+    std::fs::write(&files_from, list).unwrap();
+    let args = vec![
+        "dog|accidentally",
+        "--files-from",
+        files_from.to_str().unwrap(),
+    ];
+    let expected_exit_code = 0;
+    let expected_screen_output = ["dog\u{fffd}.txt:1:the dog barked\n", "test/file1.txt:1:"];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn basic_match_stats() {
+    let args = vec!["accidentally", "test", "--stats"];
+    let expected_exit_code = 0;
+    let expected_screen_output = ["\n4 files walked\n\
+         1 files skipped, 1 hidden, 0 excluded, 0 not UTF-8\n\
+         0 errors\n\
+         3 files searched\n\
+         1 files matched\n\
+         1 lines matched\n\
+         1 matches\n\
+         0 replacements\n\
+         0 files rewritten\n"];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+}
+
 #[test]
 fn basic_match_line_numbers_only() {
     let args = vec!["accidentally", "test", "--line-numbers-only"];
@@ -610,6 +725,48 @@ fn colored_match_short_option_not_last_argument() {
     test(&args, expected_exit_code, &expected_screen_output);
 }
 
+#[test]
+fn colored_match_with_hyperlinks() {
+    let args = vec![
+        "accidentally.*hand",
+        "test",
+        "--colors=always",
+        "--column",
+        "--hyperlinks=editor://{path}:{line}:{column}",
+    ];
+    let expected_exit_code = 0;
+    // The absolute path depends on where the tests are run.
+    let expected_screen_output = [
+        "\u{1b}[35m\u{1b}]8;;editor://",
+        "/test/file1.txt:1:5\u{1b}\\test/file1.txt:1:5\u{1b}]8;;\u{1b}\\:\u{1b}[0mThe \
+         \u{1b}[1;31maccidentally ghastly hand\u{1b}[0m plans AN ESCAPE from a cream puff the \
+         placid widow. A slovenly\n",
+    ];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+}
+
+#[test]
+fn colored_match_multiple_patterns() {
+    let args = vec![
+        "-e",
+        "ghastly",
+        "test/file1.txt",
+        "-e",
+        "widow",
+        "--colors=always",
+    ];
+    let expected_exit_code = 0;
+    let expected_screen_output = [
+        "\u{1b}[35mtest/file1.txt:1:\u{1b}[0mThe accidentally \u{1b}[1;31mghastly\u{1b}[0m hand \
+         plans AN ESCAPE from a cream puff the placid \u{1b}[1;34mwidow\u{1b}[0m. A slovenly\n",
+        "\u{1b}[35mtest/file1.txt:4:\u{1b}[0mbefriends an unseemly dilettante! Unlike so many \
+         mastadons who have made their lovely \u{1b}[1;34mwidow\u{1b}[0m\n",
+    ];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+}
+
 #[test]
 fn colored_match_whole_files() {
     let args = vec![
@@ -812,24 +969,68 @@ test/longfile.txt:1:The bodice ripper writes a love letter to a comely dissident
 test/longfile.txt:2:Interloper and I took a self-actualized dissident (with the wisely
 test/longfile.txt:3:sprightly necromancer, another dissident, a few mirrors, and the
 test/longfile.txt:4:looking glass near a taxidermist) to arrive at a state of intimacy
-test/longfile.txt:5:where we can secretly give lectures on morality to our cream puff.
+test/longfile.txt-5-where we can secretly give lectures on morality to our cream puff.
+--
 test/longfile.txt:10:The dilettante defined by a clock ceases to exist, and the looking
-test/longfile.txt:11:glass seeks the lovely trombone. The toothache hardly trades baseball
+test/longfile.txt-11-glass seeks the lovely trombone. The toothache hardly trades baseball
+--
 test/longfile.txt:13:caricatures a dissident. Most people believe that a ghastly gonad
 test/longfile.txt:14:gives lectures on morality to the wisely darling toothpick, but they
-test/longfile.txt:15:need to remember how hesitantly a bonbon daydreams. A widow somewhat
+test/longfile.txt-15-need to remember how hesitantly a bonbon daydreams. A widow somewhat
+--
 test/longfile.txt:17:soothed by an espadrille and a fetishist, still makes a truce with her
 test/longfile.txt:18:from an unseemly gypsy, buy an expensive gift for her a fetishist with
-test/longfile.txt:19:a philosopher, and takes a peek at the dark side of her dilettante.
+test/longfile.txt-19-a philosopher, and takes a peek at the dark side of her dilettante.
+--
 test/longfile.txt:24:where we can feverishly play pinochle with our trombone. The boy for a
-test/longfile.txt:25:shadow, a gypsy living with a boy, and some toothpick for another
+test/longfile.txt-25-shadow, a gypsy living with a boy, and some toothpick for another
+--
 test/longfile.txt:30:cards with an impresario. The labyrinth related to the menag\u{e9} \u{e0} \
 trois
-test/longfile.txt:31:lazily secretly admires the boy beyond a tea party. He called her Lila
+test/longfile.txt-31-lazily secretly admires the boy beyond a tea party. He called her Lila
+--
 test/longfile.txt:33:fetishist defined by a marzipan and a clodhopper, still amorously
-test/longfile.txt:34:teaches her from a gonad behind an impresario, bestow great honor upon
+test/longfile.txt-34-teaches her from a gonad behind an impresario, bestow great honor upon
 test/longfile.txt:35:her the lovely fetishist with a cup beyond the pocket, and lazily
-test/longfile.txt:36:boogies the dark side of her
+test/longfile.txt-36-boogies the dark side of her
+"];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+}
+
+#[test]
+fn heading() {
+    let args = vec!["widow", "test/file1.txt", "test/longfile.txt", "--heading"];
+    let expected_exit_code = 0;
+    let expected_screen_output = ["\
+test/file1.txt
+1:The accidentally ghastly hand plans AN ESCAPE from a cream puff the placid widow. A slovenly
+4:befriends an unseemly dilettante! Unlike so many mastadons who have made their lovely widow
+
+test/longfile.txt
+15:need to remember how hesitantly a bonbon daydreams. A widow somewhat
+16:caricatures the widow from a tea party. Nicolas, although somewhat
+"];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+}
+
+#[test]
+fn no_heading() {
+    let args = vec![
+        "widow",
+        "test/file1.txt",
+        "test/longfile.txt",
+        "--heading",
+        "--no-heading",
+    ]; // --no-heading takes precedence.
+    let expected_exit_code = 0;
+    let expected_screen_output = ["\
+test/file1.txt:1:The accidentally ghastly hand plans AN ESCAPE from a cream puff the placid widow. \
+A slovenly
+test/file1.txt:4:befriends an unseemly dilettante! Unlike so many mastadons who have made their \
+lovely widow
+test/longfile.txt:15:need to remember how hesitantly a bonbon daydreams. A widow somewhat
 "];
 
     test(&args, expected_exit_code, &expected_screen_output);
@@ -844,21 +1045,27 @@ test/longfile.txt:1:The bodice ripper writes a love letter to a comely dissident
 test/longfile.txt:2:Interloper and I took a self-actualized dissident (with the wisely
 test/longfile.txt:3:sprightly necromancer, another dissident, a few mirrors, and the
 test/longfile.txt:4:looking glass near a taxidermist) to arrive at a state of intimacy
-test/longfile.txt:9:and a trombone defined by a shadow are what got Nimbo into trouble.
+--
+test/longfile.txt-9-and a trombone defined by a shadow are what got Nimbo into trouble.
 test/longfile.txt:10:The dilettante defined by a clock ceases to exist, and the looking
-test/longfile.txt:12:cards with a amour-propre, but a non-chalantly sublime bubble almost
+--
+test/longfile.txt-12-cards with a amour-propre, but a non-chalantly sublime bubble almost
 test/longfile.txt:13:caricatures a dissident. Most people believe that a ghastly gonad
 test/longfile.txt:14:gives lectures on morality to the wisely darling toothpick, but they
-test/longfile.txt:16:caricatures the widow from a tea party. Nicolas, although somewhat
+--
+test/longfile.txt-16-caricatures the widow from a tea party. Nicolas, although somewhat
 test/longfile.txt:17:soothed by an espadrille and a fetishist, still makes a truce with her
 test/longfile.txt:18:from an unseemly gypsy, buy an expensive gift for her a fetishist with
-test/longfile.txt:23:and the cleavage behind the bride) to arrive at a state of intimacy
+--
+test/longfile.txt-23-and the cleavage behind the bride) to arrive at a state of intimacy
 test/longfile.txt:24:where we can feverishly play pinochle with our trombone. The boy for a
-test/longfile.txt:29:bubble living with a ruffian. Some darling toothache trades baseball
+--
+test/longfile.txt-29-bubble living with a ruffian. Some darling toothache trades baseball
 test/longfile.txt:30:cards with an impresario. The labyrinth related to the menagé à trois
-test/longfile.txt:32:(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
+--
+test/longfile.txt-32-(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
 test/longfile.txt:33:fetishist defined by a marzipan and a clodhopper, still amorously
-test/longfile.txt:34:teaches her from a gonad behind an impresario, bestow great honor upon
+test/longfile.txt-34-teaches her from a gonad behind an impresario, bestow great honor upon
 test/longfile.txt:35:her the lovely fetishist with a cup beyond the pocket, and lazily
 "];
 
@@ -874,29 +1081,32 @@ test/longfile.txt:1:The bodice ripper writes a love letter to a comely dissident
 test/longfile.txt:2:Interloper and I took a self-actualized dissident (with the wisely
 test/longfile.txt:3:sprightly necromancer, another dissident, a few mirrors, and the
 test/longfile.txt:4:looking glass near a taxidermist) to arrive at a state of intimacy
-test/longfile.txt:5:where we can secretly give lectures on morality to our cream puff.
-test/longfile.txt:9:and a trombone defined by a shadow are what got Nimbo into trouble.
+test/longfile.txt-5-where we can secretly give lectures on morality to our cream puff.
+--
+test/longfile.txt-9-and a trombone defined by a shadow are what got Nimbo into trouble.
 test/longfile.txt:10:The dilettante defined by a clock ceases to exist, and the looking
-test/longfile.txt:11:glass seeks the lovely trombone. The toothache hardly trades baseball
-test/longfile.txt:12:cards with a amour-propre, but a non-chalantly sublime bubble almost
+test/longfile.txt-11-glass seeks the lovely trombone. The toothache hardly trades baseball
+test/longfile.txt-12-cards with a amour-propre, but a non-chalantly sublime bubble almost
 test/longfile.txt:13:caricatures a dissident. Most people believe that a ghastly gonad
 test/longfile.txt:14:gives lectures on morality to the wisely darling toothpick, but they
-test/longfile.txt:15:need to remember how hesitantly a bonbon daydreams. A widow somewhat
-test/longfile.txt:16:caricatures the widow from a tea party. Nicolas, although somewhat
+test/longfile.txt-15-need to remember how hesitantly a bonbon daydreams. A widow somewhat
+test/longfile.txt-16-caricatures the widow from a tea party. Nicolas, although somewhat
 test/longfile.txt:17:soothed by an espadrille and a fetishist, still makes a truce with her
 test/longfile.txt:18:from an unseemly gypsy, buy an expensive gift for her a fetishist with
-test/longfile.txt:19:a philosopher, and takes a peek at the dark side of her dilettante.
-test/longfile.txt:23:and the cleavage behind the bride) to arrive at a state of intimacy
+test/longfile.txt-19-a philosopher, and takes a peek at the dark side of her dilettante.
+--
+test/longfile.txt-23-and the cleavage behind the bride) to arrive at a state of intimacy
 test/longfile.txt:24:where we can feverishly play pinochle with our trombone. The boy for a
-test/longfile.txt:25:shadow, a gypsy living with a boy, and some toothpick for another
-test/longfile.txt:29:bubble living with a ruffian. Some darling toothache trades baseball
+test/longfile.txt-25-shadow, a gypsy living with a boy, and some toothpick for another
+--
+test/longfile.txt-29-bubble living with a ruffian. Some darling toothache trades baseball
 test/longfile.txt:30:cards with an impresario. The labyrinth related to the menagé à trois
-test/longfile.txt:31:lazily secretly admires the boy beyond a tea party. He called her Lila
-test/longfile.txt:32:(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
+test/longfile.txt-31-lazily secretly admires the boy beyond a tea party. He called her Lila
+test/longfile.txt-32-(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
 test/longfile.txt:33:fetishist defined by a marzipan and a clodhopper, still amorously
-test/longfile.txt:34:teaches her from a gonad behind an impresario, bestow great honor upon
+test/longfile.txt-34-teaches her from a gonad behind an impresario, bestow great honor upon
 test/longfile.txt:35:her the lovely fetishist with a cup beyond the pocket, and lazily
-test/longfile.txt:36:boogies the dark side of her
+test/longfile.txt-36-boogies the dark side of her
 "];
 
     test(&args, expected_exit_code, &expected_screen_output);
@@ -911,30 +1121,34 @@ test/longfile.txt:1:The bodice ripper writes a love letter to a comely dissident
 test/longfile.txt:2:Interloper and I took a self-actualized dissident (with the wisely
 test/longfile.txt:3:sprightly necromancer, another dissident, a few mirrors, and the
 test/longfile.txt:4:looking glass near a taxidermist) to arrive at a state of intimacy
-test/longfile.txt:5:where we can secretly give lectures on morality to our cream puff.
-test/longfile.txt:6:Toscanini, the friend of Jean-Pierre and Jacques, goes to sleep with
+test/longfile.txt-5-where we can secretly give lectures on morality to our cream puff.
+test/longfile.txt-6-Toscanini, the friend of Jean-Pierre and Jacques, goes to sleep with
+--
 test/longfile.txt:10:The dilettante defined by a clock ceases to exist, and the looking
-test/longfile.txt:11:glass seeks the lovely trombone. The toothache hardly trades baseball
-test/longfile.txt:12:cards with a amour-propre, but a non-chalantly sublime bubble almost
+test/longfile.txt-11-glass seeks the lovely trombone. The toothache hardly trades baseball
+test/longfile.txt-12-cards with a amour-propre, but a non-chalantly sublime bubble almost
 test/longfile.txt:13:caricatures a dissident. Most people believe that a ghastly gonad
 test/longfile.txt:14:gives lectures on morality to the wisely darling toothpick, but they
-test/longfile.txt:15:need to remember how hesitantly a bonbon daydreams. A widow somewhat
-test/longfile.txt:16:caricatures the widow from a tea party. Nicolas, although somewhat
+test/longfile.txt-15-need to remember how hesitantly a bonbon daydreams. A widow somewhat
+test/longfile.txt-16-caricatures the widow from a tea party. Nicolas, although somewhat
 test/longfile.txt:17:soothed by an espadrille and a fetishist, still makes a truce with her
 test/longfile.txt:18:from an unseemly gypsy, buy an expensive gift for her a fetishist with
-test/longfile.txt:19:a philosopher, and takes a peek at the dark side of her dilettante.
-test/longfile.txt:20:When a wobbly coward trembles, a ballerina for a cup rejoices. A
+test/longfile.txt-19-a philosopher, and takes a peek at the dark side of her dilettante.
+test/longfile.txt-20-When a wobbly coward trembles, a ballerina for a cup rejoices. A
+--
 test/longfile.txt:24:where we can feverishly play pinochle with our trombone. The boy for a
-test/longfile.txt:25:shadow, a gypsy living with a boy, and some toothpick for another
-test/longfile.txt:26:ballerina are what got Timosha into trouble. Sometimes the piroshki
+test/longfile.txt-25-shadow, a gypsy living with a boy, and some toothpick for another
+test/longfile.txt-26-ballerina are what got Timosha into trouble. Sometimes the piroshki
+--
 test/longfile.txt:30:cards with an impresario. The labyrinth related to the menagé à trois
-test/longfile.txt:31:lazily secretly admires the boy beyond a tea party. He called her Lila
-test/longfile.txt:32:(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
+test/longfile.txt-31-lazily secretly admires the boy beyond a tea party. He called her Lila
+test/longfile.txt-32-(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
 test/longfile.txt:33:fetishist defined by a marzipan and a clodhopper, still amorously
-test/longfile.txt:34:teaches her from a gonad behind an impresario, bestow great honor upon
+test/longfile.txt-34-teaches her from a gonad behind an impresario, bestow great honor upon
 test/longfile.txt:35:her the lovely fetishist with a cup beyond the pocket, and lazily
-test/longfile.txt:36:boogies the dark side of her
-test/longfile.txt:37:snow."];
+test/longfile.txt-36-boogies the dark side of her
+test/longfile.txt-37-snow.
+"];
 
     test(&args, expected_exit_code, &expected_screen_output);
 }
@@ -948,27 +1162,30 @@ test/longfile.txt:1:The bodice ripper writes a love letter to a comely dissident
 test/longfile.txt:2:Interloper and I took a self-actualized dissident (with the wisely
 test/longfile.txt:3:sprightly necromancer, another dissident, a few mirrors, and the
 test/longfile.txt:4:looking glass near a taxidermist) to arrive at a state of intimacy
-test/longfile.txt:8:botched hand. A bubble inside a dilettante, a swamp for the lunatic,
-test/longfile.txt:9:and a trombone defined by a shadow are what got Nimbo into trouble.
+--
+test/longfile.txt-8-botched hand. A bubble inside a dilettante, a swamp for the lunatic,
+test/longfile.txt-9-and a trombone defined by a shadow are what got Nimbo into trouble.
 test/longfile.txt:10:The dilettante defined by a clock ceases to exist, and the looking
-test/longfile.txt:11:glass seeks the lovely trombone. The toothache hardly trades baseball
-test/longfile.txt:12:cards with a amour-propre, but a non-chalantly sublime bubble almost
+test/longfile.txt-11-glass seeks the lovely trombone. The toothache hardly trades baseball
+test/longfile.txt-12-cards with a amour-propre, but a non-chalantly sublime bubble almost
 test/longfile.txt:13:caricatures a dissident. Most people believe that a ghastly gonad
 test/longfile.txt:14:gives lectures on morality to the wisely darling toothpick, but they
-test/longfile.txt:15:need to remember how hesitantly a bonbon daydreams. A widow somewhat
-test/longfile.txt:16:caricatures the widow from a tea party. Nicolas, although somewhat
+test/longfile.txt-15-need to remember how hesitantly a bonbon daydreams. A widow somewhat
+test/longfile.txt-16-caricatures the widow from a tea party. Nicolas, although somewhat
 test/longfile.txt:17:soothed by an espadrille and a fetishist, still makes a truce with her
 test/longfile.txt:18:from an unseemly gypsy, buy an expensive gift for her a fetishist with
-test/longfile.txt:22:(with a rhetorical haunch, another rascally cigar, a few omphaloss,
-test/longfile.txt:23:and the cleavage behind the bride) to arrive at a state of intimacy
+--
+test/longfile.txt-22-(with a rhetorical haunch, another rascally cigar, a few omphaloss,
+test/longfile.txt-23-and the cleavage behind the bride) to arrive at a state of intimacy
 test/longfile.txt:24:where we can feverishly play pinochle with our trombone. The boy for a
-test/longfile.txt:28:bestows great honor upon a wobbly clodhopper! A girl takes a peek at a
-test/longfile.txt:29:bubble living with a ruffian. Some darling toothache trades baseball
+--
+test/longfile.txt-28-bestows great honor upon a wobbly clodhopper! A girl takes a peek at a
+test/longfile.txt-29-bubble living with a ruffian. Some darling toothache trades baseball
 test/longfile.txt:30:cards with an impresario. The labyrinth related to the menagé à trois
-test/longfile.txt:31:lazily secretly admires the boy beyond a tea party. He called her Lila
-test/longfile.txt:32:(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
+test/longfile.txt-31-lazily secretly admires the boy beyond a tea party. He called her Lila
+test/longfile.txt-32-(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
 test/longfile.txt:33:fetishist defined by a marzipan and a clodhopper, still amorously
-test/longfile.txt:34:teaches her from a gonad behind an impresario, bestow great honor upon
+test/longfile.txt-34-teaches her from a gonad behind an impresario, bestow great honor upon
 test/longfile.txt:35:her the lovely fetishist with a cup beyond the pocket, and lazily
 "];
 
@@ -984,36 +1201,39 @@ test/longfile.txt:1:The bodice ripper writes a love letter to a comely dissident
 test/longfile.txt:2:Interloper and I took a self-actualized dissident (with the wisely
 test/longfile.txt:3:sprightly necromancer, another dissident, a few mirrors, and the
 test/longfile.txt:4:looking glass near a taxidermist) to arrive at a state of intimacy
-test/longfile.txt:5:where we can secretly give lectures on morality to our cream puff.
-test/longfile.txt:6:Toscanini, the friend of Jean-Pierre and Jacques, goes to sleep with
-test/longfile.txt:8:botched hand. A bubble inside a dilettante, a swamp for the lunatic,
-test/longfile.txt:9:and a trombone defined by a shadow are what got Nimbo into trouble.
+test/longfile.txt-5-where we can secretly give lectures on morality to our cream puff.
+test/longfile.txt-6-Toscanini, the friend of Jean-Pierre and Jacques, goes to sleep with
+--
+test/longfile.txt-8-botched hand. A bubble inside a dilettante, a swamp for the lunatic,
+test/longfile.txt-9-and a trombone defined by a shadow are what got Nimbo into trouble.
 test/longfile.txt:10:The dilettante defined by a clock ceases to exist, and the looking
-test/longfile.txt:11:glass seeks the lovely trombone. The toothache hardly trades baseball
-test/longfile.txt:12:cards with a amour-propre, but a non-chalantly sublime bubble almost
+test/longfile.txt-11-glass seeks the lovely trombone. The toothache hardly trades baseball
+test/longfile.txt-12-cards with a amour-propre, but a non-chalantly sublime bubble almost
 test/longfile.txt:13:caricatures a dissident. Most people believe that a ghastly gonad
 test/longfile.txt:14:gives lectures on morality to the wisely darling toothpick, but they
-test/longfile.txt:15:need to remember how hesitantly a bonbon daydreams. A widow somewhat
-test/longfile.txt:16:caricatures the widow from a tea party. Nicolas, although somewhat
+test/longfile.txt-15-need to remember how hesitantly a bonbon daydreams. A widow somewhat
+test/longfile.txt-16-caricatures the widow from a tea party. Nicolas, although somewhat
 test/longfile.txt:17:soothed by an espadrille and a fetishist, still makes a truce with her
 test/longfile.txt:18:from an unseemly gypsy, buy an expensive gift for her a fetishist with
-test/longfile.txt:19:a philosopher, and takes a peek at the dark side of her dilettante.
-test/longfile.txt:20:When a wobbly coward trembles, a ballerina for a cup rejoices. A
-test/longfile.txt:22:(with a rhetorical haunch, another rascally cigar, a few omphaloss,
-test/longfile.txt:23:and the cleavage behind the bride) to arrive at a state of intimacy
+test/longfile.txt-19-a philosopher, and takes a peek at the dark side of her dilettante.
+test/longfile.txt-20-When a wobbly coward trembles, a ballerina for a cup rejoices. A
+--
+test/longfile.txt-22-(with a rhetorical haunch, another rascally cigar, a few omphaloss,
+test/longfile.txt-23-and the cleavage behind the bride) to arrive at a state of intimacy
 test/longfile.txt:24:where we can feverishly play pinochle with our trombone. The boy for a
-test/longfile.txt:25:shadow, a gypsy living with a boy, and some toothpick for another
-test/longfile.txt:26:ballerina are what got Timosha into trouble. Sometimes the piroshki
-test/longfile.txt:28:bestows great honor upon a wobbly clodhopper! A girl takes a peek at a
-test/longfile.txt:29:bubble living with a ruffian. Some darling toothache trades baseball
+test/longfile.txt-25-shadow, a gypsy living with a boy, and some toothpick for another
+test/longfile.txt-26-ballerina are what got Timosha into trouble. Sometimes the piroshki
+--
+test/longfile.txt-28-bestows great honor upon a wobbly clodhopper! A girl takes a peek at a
+test/longfile.txt-29-bubble living with a ruffian. Some darling toothache trades baseball
 test/longfile.txt:30:cards with an impresario. The labyrinth related to the menagé à trois
-test/longfile.txt:31:lazily secretly admires the boy beyond a tea party. He called her Lila
-test/longfile.txt:32:(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
+test/longfile.txt-31-lazily secretly admires the boy beyond a tea party. He called her Lila
+test/longfile.txt-32-(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
 test/longfile.txt:33:fetishist defined by a marzipan and a clodhopper, still amorously
-test/longfile.txt:34:teaches her from a gonad behind an impresario, bestow great honor upon
+test/longfile.txt-34-teaches her from a gonad behind an impresario, bestow great honor upon
 test/longfile.txt:35:her the lovely fetishist with a cup beyond the pocket, and lazily
-test/longfile.txt:36:boogies the dark side of her
-test/longfile.txt:37:snow.
+test/longfile.txt-36-boogies the dark side of her
+test/longfile.txt-37-snow.
 "];
 
     test(&args, expected_exit_code, &expected_screen_output);
@@ -1028,39 +1248,39 @@ test/longfile.txt:1:The bodice ripper writes a love letter to a comely dissident
 test/longfile.txt:2:Interloper and I took a self-actualized dissident (with the wisely
 test/longfile.txt:3:sprightly necromancer, another dissident, a few mirrors, and the
 test/longfile.txt:4:looking glass near a taxidermist) to arrive at a state of intimacy
-test/longfile.txt:5:where we can secretly give lectures on morality to our cream puff.
-test/longfile.txt:6:Toscanini, the friend of Jean-Pierre and Jacques, goes to sleep with
-test/longfile.txt:7:the wily labyrinth. The slovenly piroshki ostensibly teaches the
-test/longfile.txt:8:botched hand. A bubble inside a dilettante, a swamp for the lunatic,
-test/longfile.txt:9:and a trombone defined by a shadow are what got Nimbo into trouble.
+test/longfile.txt-5-where we can secretly give lectures on morality to our cream puff.
+test/longfile.txt-6-Toscanini, the friend of Jean-Pierre and Jacques, goes to sleep with
+test/longfile.txt-7-the wily labyrinth. The slovenly piroshki ostensibly teaches the
+test/longfile.txt-8-botched hand. A bubble inside a dilettante, a swamp for the lunatic,
+test/longfile.txt-9-and a trombone defined by a shadow are what got Nimbo into trouble.
 test/longfile.txt:10:The dilettante defined by a clock ceases to exist, and the looking
-test/longfile.txt:11:glass seeks the lovely trombone. The toothache hardly trades baseball
-test/longfile.txt:12:cards with a amour-propre, but a non-chalantly sublime bubble almost
+test/longfile.txt-11-glass seeks the lovely trombone. The toothache hardly trades baseball
+test/longfile.txt-12-cards with a amour-propre, but a non-chalantly sublime bubble almost
 test/longfile.txt:13:caricatures a dissident. Most people believe that a ghastly gonad
 test/longfile.txt:14:gives lectures on morality to the wisely darling toothpick, but they
-test/longfile.txt:15:need to remember how hesitantly a bonbon daydreams. A widow somewhat
-test/longfile.txt:16:caricatures the widow from a tea party. Nicolas, although somewhat
+test/longfile.txt-15-need to remember how hesitantly a bonbon daydreams. A widow somewhat
+test/longfile.txt-16-caricatures the widow from a tea party. Nicolas, although somewhat
 test/longfile.txt:17:soothed by an espadrille and a fetishist, still makes a truce with her
 test/longfile.txt:18:from an unseemly gypsy, buy an expensive gift for her a fetishist with
-test/longfile.txt:19:a philosopher, and takes a peek at the dark side of her dilettante.
-test/longfile.txt:20:When a wobbly coward trembles, a ballerina for a cup rejoices. A
-test/longfile.txt:21:gingerly curse conquers the philosopher. Toscanini and I took a bicep
-test/longfile.txt:22:(with a rhetorical haunch, another rascally cigar, a few omphaloss,
-test/longfile.txt:23:and the cleavage behind the bride) to arrive at a state of intimacy
+test/longfile.txt-19-a philosopher, and takes a peek at the dark side of her dilettante.
+test/longfile.txt-20-When a wobbly coward trembles, a ballerina for a cup rejoices. A
+test/longfile.txt-21-gingerly curse conquers the philosopher. Toscanini and I took a bicep
+test/longfile.txt-22-(with a rhetorical haunch, another rascally cigar, a few omphaloss,
+test/longfile.txt-23-and the cleavage behind the bride) to arrive at a state of intimacy
 test/longfile.txt:24:where we can feverishly play pinochle with our trombone. The boy for a
-test/longfile.txt:25:shadow, a gypsy living with a boy, and some toothpick for another
-test/longfile.txt:26:ballerina are what got Timosha into trouble. Sometimes the piroshki
-test/longfile.txt:27:toward a dahlia goes to sleep, but a girl about a piroshki always
-test/longfile.txt:28:bestows great honor upon a wobbly clodhopper! A girl takes a peek at a
-test/longfile.txt:29:bubble living with a ruffian. Some darling toothache trades baseball
+test/longfile.txt-25-shadow, a gypsy living with a boy, and some toothpick for another
+test/longfile.txt-26-ballerina are what got Timosha into trouble. Sometimes the piroshki
+test/longfile.txt-27-toward a dahlia goes to sleep, but a girl about a piroshki always
+test/longfile.txt-28-bestows great honor upon a wobbly clodhopper! A girl takes a peek at a
+test/longfile.txt-29-bubble living with a ruffian. Some darling toothache trades baseball
 test/longfile.txt:30:cards with an impresario. The labyrinth related to the menagé à trois
-test/longfile.txt:31:lazily secretly admires the boy beyond a tea party. He called her Lila
-test/longfile.txt:32:(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
+test/longfile.txt-31-lazily secretly admires the boy beyond a tea party. He called her Lila
+test/longfile.txt-32-(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
 test/longfile.txt:33:fetishist defined by a marzipan and a clodhopper, still amorously
-test/longfile.txt:34:teaches her from a gonad behind an impresario, bestow great honor upon
+test/longfile.txt-34-teaches her from a gonad behind an impresario, bestow great honor upon
 test/longfile.txt:35:her the lovely fetishist with a cup beyond the pocket, and lazily
-test/longfile.txt:36:boogies the dark side of her
-test/longfile.txt:37:snow.
+test/longfile.txt-36-boogies the dark side of her
+test/longfile.txt-37-snow.
 "];
 
     test(&args, expected_exit_code, &expected_screen_output);
@@ -1075,36 +1295,36 @@ test/longfile.txt:1:The bodice ripper writes a love letter to a comely dissident
 test/longfile.txt:2:Interloper and I took a self-actualized dissident (with the wisely
 test/longfile.txt:3:sprightly necromancer, another dissident, a few mirrors, and the
 test/longfile.txt:4:looking glass near a taxidermist) to arrive at a state of intimacy
-test/longfile.txt:5:where we can secretly give lectures on morality to our cream puff.
-test/longfile.txt:6:Toscanini, the friend of Jean-Pierre and Jacques, goes to sleep with
-test/longfile.txt:7:the wily labyrinth. The slovenly piroshki ostensibly teaches the
-test/longfile.txt:8:botched hand. A bubble inside a dilettante, a swamp for the lunatic,
-test/longfile.txt:9:and a trombone defined by a shadow are what got Nimbo into trouble.
+test/longfile.txt-5-where we can secretly give lectures on morality to our cream puff.
+test/longfile.txt-6-Toscanini, the friend of Jean-Pierre and Jacques, goes to sleep with
+test/longfile.txt-7-the wily labyrinth. The slovenly piroshki ostensibly teaches the
+test/longfile.txt-8-botched hand. A bubble inside a dilettante, a swamp for the lunatic,
+test/longfile.txt-9-and a trombone defined by a shadow are what got Nimbo into trouble.
 test/longfile.txt:10:The dilettante defined by a clock ceases to exist, and the looking
-test/longfile.txt:11:glass seeks the lovely trombone. The toothache hardly trades baseball
-test/longfile.txt:12:cards with a amour-propre, but a non-chalantly sublime bubble almost
+test/longfile.txt-11-glass seeks the lovely trombone. The toothache hardly trades baseball
+test/longfile.txt-12-cards with a amour-propre, but a non-chalantly sublime bubble almost
 test/longfile.txt:13:caricatures a dissident. Most people believe that a ghastly gonad
 test/longfile.txt:14:gives lectures on morality to the wisely darling toothpick, but they
-test/longfile.txt:15:need to remember how hesitantly a bonbon daydreams. A widow somewhat
-test/longfile.txt:16:caricatures the widow from a tea party. Nicolas, although somewhat
+test/longfile.txt-15-need to remember how hesitantly a bonbon daydreams. A widow somewhat
+test/longfile.txt-16-caricatures the widow from a tea party. Nicolas, although somewhat
 test/longfile.txt:17:soothed by an espadrille and a fetishist, still makes a truce with her
 test/longfile.txt:18:from an unseemly gypsy, buy an expensive gift for her a fetishist with
-test/longfile.txt:19:a philosopher, and takes a peek at the dark side of her dilettante.
-test/longfile.txt:20:When a wobbly coward trembles, a ballerina for a cup rejoices. A
-test/longfile.txt:21:gingerly curse conquers the philosopher. Toscanini and I took a bicep
-test/longfile.txt:22:(with a rhetorical haunch, another rascally cigar, a few omphaloss,
-test/longfile.txt:23:and the cleavage behind the bride) to arrive at a state of intimacy
+test/longfile.txt-19-a philosopher, and takes a peek at the dark side of her dilettante.
+test/longfile.txt-20-When a wobbly coward trembles, a ballerina for a cup rejoices. A
+test/longfile.txt-21-gingerly curse conquers the philosopher. Toscanini and I took a bicep
+test/longfile.txt-22-(with a rhetorical haunch, another rascally cigar, a few omphaloss,
+test/longfile.txt-23-and the cleavage behind the bride) to arrive at a state of intimacy
 test/longfile.txt:24:where we can feverishly play pinochle with our trombone. The boy for a
-test/longfile.txt:25:shadow, a gypsy living with a boy, and some toothpick for another
-test/longfile.txt:26:ballerina are what got Timosha into trouble. Sometimes the piroshki
-test/longfile.txt:27:toward a dahlia goes to sleep, but a girl about a piroshki always
-test/longfile.txt:28:bestows great honor upon a wobbly clodhopper! A girl takes a peek at a
-test/longfile.txt:29:bubble living with a ruffian. Some darling toothache trades baseball
+test/longfile.txt-25-shadow, a gypsy living with a boy, and some toothpick for another
+test/longfile.txt-26-ballerina are what got Timosha into trouble. Sometimes the piroshki
+test/longfile.txt-27-toward a dahlia goes to sleep, but a girl about a piroshki always
+test/longfile.txt-28-bestows great honor upon a wobbly clodhopper! A girl takes a peek at a
+test/longfile.txt-29-bubble living with a ruffian. Some darling toothache trades baseball
 test/longfile.txt:30:cards with an impresario. The labyrinth related to the menagé à trois
-test/longfile.txt:31:lazily secretly admires the boy beyond a tea party. He called her Lila
-test/longfile.txt:32:(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
+test/longfile.txt-31-lazily secretly admires the boy beyond a tea party. He called her Lila
+test/longfile.txt-32-(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
 test/longfile.txt:33:fetishist defined by a marzipan and a clodhopper, still amorously
-test/longfile.txt:34:teaches her from a gonad behind an impresario, bestow great honor upon
+test/longfile.txt-34-teaches her from a gonad behind an impresario, bestow great honor upon
 test/longfile.txt:35:her the lovely fetishist with a cup beyond the pocket, and lazily
 "];
 
@@ -1120,39 +1340,39 @@ test/longfile.txt:1:The bodice ripper writes a love letter to a comely dissident
 test/longfile.txt:2:Interloper and I took a self-actualized dissident (with the wisely
 test/longfile.txt:3:sprightly necromancer, another dissident, a few mirrors, and the
 test/longfile.txt:4:looking glass near a taxidermist) to arrive at a state of intimacy
-test/longfile.txt:5:where we can secretly give lectures on morality to our cream puff.
-test/longfile.txt:6:Toscanini, the friend of Jean-Pierre and Jacques, goes to sleep with
-test/longfile.txt:7:the wily labyrinth. The slovenly piroshki ostensibly teaches the
-test/longfile.txt:8:botched hand. A bubble inside a dilettante, a swamp for the lunatic,
-test/longfile.txt:9:and a trombone defined by a shadow are what got Nimbo into trouble.
+test/longfile.txt-5-where we can secretly give lectures on morality to our cream puff.
+test/longfile.txt-6-Toscanini, the friend of Jean-Pierre and Jacques, goes to sleep with
+test/longfile.txt-7-the wily labyrinth. The slovenly piroshki ostensibly teaches the
+test/longfile.txt-8-botched hand. A bubble inside a dilettante, a swamp for the lunatic,
+test/longfile.txt-9-and a trombone defined by a shadow are what got Nimbo into trouble.
 test/longfile.txt:10:The dilettante defined by a clock ceases to exist, and the looking
-test/longfile.txt:11:glass seeks the lovely trombone. The toothache hardly trades baseball
-test/longfile.txt:12:cards with a amour-propre, but a non-chalantly sublime bubble almost
+test/longfile.txt-11-glass seeks the lovely trombone. The toothache hardly trades baseball
+test/longfile.txt-12-cards with a amour-propre, but a non-chalantly sublime bubble almost
 test/longfile.txt:13:caricatures a dissident. Most people believe that a ghastly gonad
 test/longfile.txt:14:gives lectures on morality to the wisely darling toothpick, but they
-test/longfile.txt:15:need to remember how hesitantly a bonbon daydreams. A widow somewhat
-test/longfile.txt:16:caricatures the widow from a tea party. Nicolas, although somewhat
+test/longfile.txt-15-need to remember how hesitantly a bonbon daydreams. A widow somewhat
+test/longfile.txt-16-caricatures the widow from a tea party. Nicolas, although somewhat
 test/longfile.txt:17:soothed by an espadrille and a fetishist, still makes a truce with her
 test/longfile.txt:18:from an unseemly gypsy, buy an expensive gift for her a fetishist with
-test/longfile.txt:19:a philosopher, and takes a peek at the dark side of her dilettante.
-test/longfile.txt:20:When a wobbly coward trembles, a ballerina for a cup rejoices. A
-test/longfile.txt:21:gingerly curse conquers the philosopher. Toscanini and I took a bicep
-test/longfile.txt:22:(with a rhetorical haunch, another rascally cigar, a few omphaloss,
-test/longfile.txt:23:and the cleavage behind the bride) to arrive at a state of intimacy
+test/longfile.txt-19-a philosopher, and takes a peek at the dark side of her dilettante.
+test/longfile.txt-20-When a wobbly coward trembles, a ballerina for a cup rejoices. A
+test/longfile.txt-21-gingerly curse conquers the philosopher. Toscanini and I took a bicep
+test/longfile.txt-22-(with a rhetorical haunch, another rascally cigar, a few omphaloss,
+test/longfile.txt-23-and the cleavage behind the bride) to arrive at a state of intimacy
 test/longfile.txt:24:where we can feverishly play pinochle with our trombone. The boy for a
-test/longfile.txt:25:shadow, a gypsy living with a boy, and some toothpick for another
-test/longfile.txt:26:ballerina are what got Timosha into trouble. Sometimes the piroshki
-test/longfile.txt:27:toward a dahlia goes to sleep, but a girl about a piroshki always
-test/longfile.txt:28:bestows great honor upon a wobbly clodhopper! A girl takes a peek at a
-test/longfile.txt:29:bubble living with a ruffian. Some darling toothache trades baseball
+test/longfile.txt-25-shadow, a gypsy living with a boy, and some toothpick for another
+test/longfile.txt-26-ballerina are what got Timosha into trouble. Sometimes the piroshki
+test/longfile.txt-27-toward a dahlia goes to sleep, but a girl about a piroshki always
+test/longfile.txt-28-bestows great honor upon a wobbly clodhopper! A girl takes a peek at a
+test/longfile.txt-29-bubble living with a ruffian. Some darling toothache trades baseball
 test/longfile.txt:30:cards with an impresario. The labyrinth related to the menagé à trois
-test/longfile.txt:31:lazily secretly admires the boy beyond a tea party. He called her Lila
-test/longfile.txt:32:(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
+test/longfile.txt-31-lazily secretly admires the boy beyond a tea party. He called her Lila
+test/longfile.txt-32-(or was it Harpo Marx?). Nicolas, although somewhat soothed by the
 test/longfile.txt:33:fetishist defined by a marzipan and a clodhopper, still amorously
-test/longfile.txt:34:teaches her from a gonad behind an impresario, bestow great honor upon
+test/longfile.txt-34-teaches her from a gonad behind an impresario, bestow great honor upon
 test/longfile.txt:35:her the lovely fetishist with a cup beyond the pocket, and lazily
-test/longfile.txt:36:boogies the dark side of her
-test/longfile.txt:37:snow.
+test/longfile.txt-36-boogies the dark side of her
+test/longfile.txt-37-snow.
 "];
 
     test(&args, expected_exit_code, &expected_screen_output);
@@ -1429,8 +1649,83 @@ fn recursive_match_line_numbers_only_no_match() {
     test(&args, expected_exit_code, &expected_screen_output);
 }
 
+#[test]
+fn patch_paths_are_relative_to_the_current_directory() {
+    let absolute = std::env::current_dir()
+        .unwrap()
+        .join("test")
+        .join("file1.txt");
+    for file in &[absolute.to_str().unwrap(), "./test/../test/file1.txt"] {
+        let args = vec!["accidentally", file, "-r", "x", "--patch"];
+        let expected_exit_code = 0;
+        let expected_screen_output = ["--- a/test/file1.txt\n+++ b/test/file1.txt\n"];
+
+        test(&args, expected_exit_code, &expected_screen_output);
+    }
+}
+
+#[test]
+fn patch_of_file_outside_the_current_directory() {
+    let file = std::env::temp_dir().join(format!("ned_test_patch_{}.txt", std::process::id()));
+    std::fs::write(&file, "dog\n").unwrap();
+    let args = vec!["dog", file.to_str().unwrap(), "-r", "cat", "--patch"];
+    let expected_exit_code = 1;
+    let expected_screen_output = [""];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "dog\n");
+    let _ = std::fs::remove_file(&file);
+}
+
+#[test]
+fn json_summary() {
+    let args = vec![
+        "accidentally",
+        "test/file1.txt",
+        "--json",
+        "-r",
+        "x",
+        "--stdout",
+    ];
+    let expected_exit_code = 0;
+    // The original keys come first, followed by those that --stats shows.
+    let expected_screen_output = [
+        "{\"type\":\"summary\",\"files\":1,\"files_with_matches\":1,\"matches\":1,\
+         \"replacements\":1,\"files_walked\":1,",
+        "\"files_searched\":1,\"files_matched\":1,\"lines_matched\":1,",
+    ];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+}
+
 // These tests look for each of the file's matches it expects to be in the screen output, which
 // can be in any order, because the order that walkdir walks directories is undefined.
+#[test]
+fn sarif() {
+    let args = vec![
+        "ghastly (hand)",
+        "./test/file1.txt",
+        "--sarif",
+        "--sarif-rule=hands",
+    ];
+    let expected_exit_code = 0;
+    let expected_screen_output = [
+        "{\"version\":\"2.1.0\",\
+         \"$schema\":\"https://json.schemastore.org/sarif-2.1.0.json\",\
+         \"runs\":[{\"tool\":{\"driver\":{\"name\":\"ned\",\"version\":\"",
+        "\",\"informationUri\":\"https://github.com/nevdelap/ned\",\
+         \"rules\":[{\"id\":\"hands\",\"shortDescription\":{\"text\":\"Matches of ghastly (hand)\"}}]}},\
+         \"columnKind\":\"unicodeCodePoints\",\
+         \"results\":[{\"ruleId\":\"hands\",\"ruleIndex\":0,\"level\":\"warning\",\
+         \"message\":{\"text\":\"Matches hands: ghastly hand\"},\
+         \"locations\":[{\"physicalLocation\":{\"artifactLocation\":{\"uri\":\"test/file1.txt\"},\
+         \"region\":{\"startLine\":1,\"startColumn\":18,\"endLine\":1,\"endColumn\":30,\
+         \"snippet\":{\"text\":\"ghastly hand\"}}}}]}]}]}\n",
+    ];
+
+    test(&args, expected_exit_code, &expected_screen_output);
+}
+
 fn test(args: &[&str], expected_exit_code: i32, expected_screen_output: &[&str]) {
     let args: Vec<String> = args
         .iter()
@@ -1440,7 +1735,7 @@ fn test(args: &[&str], expected_exit_code: i32, expected_screen_output: &[&str])
 
     let mut screen_output: Vec<u8> = vec![];
 
-    let exit_code = ned(&mut screen_output, &args).unwrap();
+    let exit_code = ned(&mut screen_output, &args, false).unwrap();
 
     let screen_output = fix_output_for_windows(&String::from_utf8(screen_output).unwrap());
 
@@ -0,0 +1,43 @@
+//
+// ned, https://github.com/nevdelap/ned, tests/hyperlinks.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+use crate::hyperlinks::url_path;
+use std::path::Path;
+
+#[cfg(unix)]
+#[test]
+fn url_path_unix() {
+    assert_eq!(url_path(Path::new("/home/me/a b.txt")), "home/me/a%20b.txt");
+    // A backslash is part of a file name, not a separator.
+    assert_eq!(url_path(Path::new("/tmp/a\\b.txt")), "tmp/a%5Cb.txt");
+}
+
+#[cfg(windows)]
+#[test]
+fn url_path_windows() {
+    assert_eq!(
+        url_path(Path::new(r"\\?\C:\Users\me\a b.txt")),
+        "C:/Users/me/a%20b.txt"
+    );
+    assert_eq!(
+        url_path(Path::new(r"C:\Users\me\a.txt")),
+        "C:/Users/me/a.txt"
+    );
+}
@@ -0,0 +1,254 @@
+//
+// ned, https://github.com/nevdelap/ned, tests/interactive.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+/// Test --interactive replacements - the questions asked, and what the answers do.
+use crate::options_with_defaults::OptionsWithDefaults;
+use crate::opts::make_opts;
+use crate::parameters::get_parameters;
+use crate::process_file;
+use crate::run_state::RunState;
+use crate::source::Source;
+use std::env;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+
+#[test]
+fn answer_yes_and_no() {
+    let input = "\
+one dog
+two dog
+three dog
+";
+    let args = "dog -r cat --interactive dummy";
+    let answers = "y\nn\ny\n";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:1:
+-one dog
++one cat
+Replace? [y,n,a,q] bogus_file.txt:2:
+-two dog
++two cat
+Replace? [y,n,a,q] bogus_file.txt:3:
+-three dog
++three cat
+Replace? [y,n,a,q] ";
+    let expected_file_content = "\
+one cat
+two dog
+three cat
+";
+    let expected_quit = false;
+
+    test(
+        input,
+        args,
+        answers,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+        expected_quit,
+    );
+}
+
+#[test]
+fn answer_all_replaces_the_rest_of_the_file() {
+    let input = "\
+one dog
+two dog dog
+three dog
+";
+    let args = "dog -r cat --interactive dummy";
+    let answers = "n\na\n";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:1:
+-one dog
++one cat
+Replace? [y,n,a,q] bogus_file.txt:2:
+-two dog dog
++two cat dog
+Replace? [y,n,a,q] ";
+    let expected_file_content = "\
+one dog
+two cat cat
+three cat
+";
+    let expected_quit = false;
+
+    test(
+        input,
+        args,
+        answers,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+        expected_quit,
+    );
+}
+
+#[test]
+fn answer_quit_keeps_replacements_already_made() {
+    let input = "\
+one dog
+two dog
+three dog
+";
+    let args = "dog -r cat --interactive dummy";
+    let answers = "y\nq\n";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:1:
+-one dog
++one cat
+Replace? [y,n,a,q] bogus_file.txt:2:
+-two dog
++two cat
+Replace? [y,n,a,q] ";
+    let expected_file_content = "\
+one cat
+two dog
+three dog
+";
+    let expected_quit = true;
+
+    test(
+        input,
+        args,
+        answers,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+        expected_quit,
+    );
+}
+
+#[test]
+fn unrecognised_answer_asks_again_and_no_answer_quits() {
+    let input = "\
+one dog
+";
+    let args = "dog -r cat --interactive dummy";
+    let answers = "maybe\n";
+    let expected_found_matches = false;
+    let expected_screen_output = "\
+bogus_file.txt:1:
+-one dog
++one cat
+Replace? [y,n,a,q] Replace? [y,n,a,q] \n";
+    let expected_file_content = "\
+one dog
+";
+    let expected_quit = true;
+
+    test(
+        input,
+        args,
+        answers,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+        expected_quit,
+    );
+}
+
+#[test]
+fn context_groups_and_number() {
+    let input = "\
+zero
+one dog dog
+two
+three
+";
+    let args = "(d)og -r ${1}ig -C 1 --number 1 --backwards --interactive dummy";
+    let answers = "y\n";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:2:
+ zero
+-one dog dog
++one dog dig
+ two
+Replace? [y,n,a,q] ";
+    let expected_file_content = "\
+zero
+one dog dig
+two
+three
+";
+    let expected_quit = false;
+
+    test(
+        input,
+        args,
+        answers,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+        expected_quit,
+    );
+}
+
+fn test(
+    input: &str,
+    args: &str,
+    answers: &str,
+    expected_found_matches: bool,
+    expected_screen_output: &str,
+    expected_file_content: &str,
+    expected_quit: bool,
+) {
+    let args = args
+        .split_whitespace()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<String>>();
+    env::set_var("NED_DEFAULTS", "");
+    let options_with_defaults = OptionsWithDefaults::new(make_opts(), &args).unwrap();
+    let parameters = get_parameters(&options_with_defaults, false).unwrap();
+
+    let mut cursor = Cursor::<Vec<u8>>::new(vec![]);
+    cursor.write_all(&input.to_string().into_bytes()).unwrap();
+    cursor.seek(SeekFrom::Start(0)).unwrap();
+    let mut file = Source::Cursor(Box::new(cursor));
+    let mut run_state = RunState::new(Box::new(Cursor::new(answers.to_string().into_bytes())));
+    let mut screen_output: Vec<u8> = vec![];
+
+    let found_matches = process_file(
+        &mut screen_output,
+        &parameters,
+        &mut run_state,
+        &Some("bogus_file.txt".to_string()),
+        &mut file,
+    )
+    .unwrap();
+
+    let screen_output = String::from_utf8(screen_output).unwrap();
+
+    let mut buffer = Vec::new();
+    if let Source::Cursor(ref mut cursor) = file {
+        let _ = cursor.seek(SeekFrom::Start(0));
+        let _ = cursor.read_to_end(&mut buffer);
+    }
+    let file_output = String::from_utf8(buffer).unwrap();
+
+    assert_eq!(found_matches, expected_found_matches);
+    assert_eq!(screen_output, expected_screen_output);
+    assert_eq!(file_output, expected_file_content);
+    assert_eq!(run_state.quit, expected_quit);
+}
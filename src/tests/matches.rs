@@ -24,9 +24,10 @@ use crate::options_with_defaults::OptionsWithDefaults;
 use crate::opts::make_opts;
 use crate::parameters::get_parameters;
 use crate::process_file;
+use crate::run_state::RunState;
 use crate::source::Source;
 use std::env;
-use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
 
 #[test]
 fn basic_match_quiet_and_not_quiet() {
@@ -1283,6 +1284,55 @@ That at a test.
     );
 }
 
+#[test]
+fn fixed_strings_quiet_and_not_quiet() {
+    let input = "\
+a.b (c) axb (c)
+a.bc a.b(c)
+A.B (C)
+";
+    let pattern = "a.b (c)";
+    let args = "-Q -i -x -o";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:1:a.b (c)
+bogus_file.txt:3:A.B (C)
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn replace_literal_replacement_quiet_and_not_quiet() {
+    let input = "\
+echo name price
+";
+    let pattern = "(name|price)";
+    let args = "-r $1${1}$$ --literal-replacement";
+    let expected_found_matches = true;
+    let expected_screen_output = "";
+    let expected_file_content = "\
+echo $1${1}$$ $1${1}$$
+";
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
 #[test]
 fn replace_skip_quiet_and_not_quiet() {
     let input = "\
@@ -1643,6 +1693,714 @@ related to the hand to an ungodly dahlia.
     );
 }
 
+#[test]
+fn replace_diff_quiet_and_not_quiet() {
+    let input = "\
+one dog
+two
+three
+four
+five
+six
+seven
+eight
+nine
+ten dog
+eleven
+";
+    let pattern = "dog";
+    let args = "-r cat --diff";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+--- bogus_file.txt
++++ bogus_file.txt
+@@ -1,4 +1,4 @@
+-one dog
++one cat
+ two
+ three
+ four
+@@ -7,5 +7,5 @@
+ seven
+ eight
+ nine
+-ten dog
++ten cat
+ eleven
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn replace_patch_quiet_and_not_quiet() {
+    let input = "\
+one dog
+two
+three
+four
+five dog";
+    let pattern = "dog";
+    let args = "-r cat --patch --diff=1";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+--- a/bogus_file.txt
++++ b/bogus_file.txt
+@@ -1,2 +1,2 @@
+-one dog
++one cat
+ two
+@@ -4,2 +4,2 @@
+ four
+-five dog
+\\ No newline at end of file
++five cat
+\\ No newline at end of file
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn replace_show_changes_quiet_and_not_quiet() {
+    let input = "\
+one dog
+two
+three
+four dog
+five";
+    let pattern = " dog\nfive|two\n";
+    let args = "--replace= -w --show-changes";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:2:
+-two
+bogus_file.txt:4:
+-four dog
+-five
++four
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn replace_show_changes_colored_quiet_and_not_quiet() {
+    let input = "\
+one dög dog
+two
+";
+    let pattern = "dog";
+    let args = "-r cat --show-changes --colors=always";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+\u{1b}[35mbogus_file.txt:1:
+\u{1b}[0m-one dög \u{1b}[1;31mdog\u{1b}[0m
++one dög \u{1b}[1;32mcat\u{1b}[0m
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn replace_diff_context_and_no_newline_at_end_quiet_and_not_quiet() {
+    let input = "\
+one dog
+two
+three dog";
+    let pattern = "dog";
+    let args = "-r cat --diff=0";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+--- bogus_file.txt
++++ bogus_file.txt
+@@ -1,1 +1,1 @@
+-one dog
++one cat
+@@ -3,1 +3,1 @@
+-three dog
+\\ No newline at end of file
++three cat
+\\ No newline at end of file
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn replace_diff_no_match_quiet_and_not_quiet() {
+    let input = "\
+one dog
+two
+";
+    let pattern = "bird";
+    let args = "-r cat --diff";
+    let expected_found_matches = false;
+    let expected_screen_output = "";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn json_matches_quiet_and_not_quiet() {
+    let input = "\
+a \"dog\"
+two
+dog\tdog
+";
+    let pattern = "(?P<first>d)(o)g";
+    let args = "--json -B 1 -k 1";
+    let expected_found_matches = true;
+    let expected_screen_output = r#"{"type":"begin","path":"bogus_file.txt"}
+{"type":"context","path":"bogus_file.txt","line_number":2,"offset":8,"text":"two"}
+{"type":"match","path":"bogus_file.txt","line_number":3,"offset":12,"text":"dog\tdog","matches":[{"text":"dog","start":4,"end":7,"pattern":0,"groups":[{"group":1,"name":"first","text":"d","start":4,"end":5},{"group":2,"name":null,"text":"o","start":5,"end":6}]}]}
+{"type":"end","path":"bogus_file.txt","lines":1,"matches":1}
+"#;
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn json_replace_quiet_and_not_quiet() {
+    let input = "\
+one dog
+two dog
+";
+    let pattern = "dog";
+    let args = "-r cat --json";
+    let expected_found_matches = true;
+    let expected_screen_output = r#"{"type":"begin","path":"bogus_file.txt"}
+{"type":"replace","path":"bogus_file.txt","replacements":2}
+{"type":"end","path":"bogus_file.txt","replacements":2}
+"#;
+    let expected_file_content = "\
+one cat
+two cat
+";
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn vimgrep_quiet_and_not_quiet() {
+    let input = "\
+the dög dog
+two
+dog
+";
+    let pattern = "d.g$|d.g ";
+    let args = "--vimgrep";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:1:5:the dög dog
+bogus_file.txt:1:9:the dög dog
+bogus_file.txt:3:1:dog
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn vimgrep_whole_files_quiet_and_not_quiet() {
+    let input = "\
+the dög dog
+two
+dog
+";
+    let pattern = "o\ndog";
+    let args = "--vimgrep -w";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:2:3:two
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn format_quiet_and_not_quiet() {
+    let input = "\
+the dög dog
+two
+dog
+";
+    let pattern = "(d.g)(?P<end>$)?";
+    let args =
+        r"--format={path}|{line}|{column}|{byte_offset}|{match}|{1}|{end}|{2}|{3}|{line_text}\t{";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt|1|5|4|dög|dög|||{3}|the dög dog\t{
+bogus_file.txt|1|9|9|dog|dog|||{3}|the dög dog\t{
+bogus_file.txt|3|1|17|dog|dog|||{3}|dog\t{
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn multiple_patterns_quiet_and_not_quiet() {
+    let input = "\
+one dog
+two cat
+three bird
+four cat dog
+";
+    // With -e there is no pattern before the files.
+    let pattern = "--regexp=dog";
+    let args = "-e cat -e (?P<name>b)ird -o";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:1:dog
+bogus_file.txt:2:cat
+bogus_file.txt:3:bird
+bogus_file.txt:4:catdog
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn format_multiple_patterns_quiet_and_not_quiet() {
+    let input = "\
+one dog
+two cat
+four cat dog
+";
+    // With -e there is no pattern before the files. Groups are numbered across the patterns.
+    let pattern = "--regexp=(d)og";
+    let args = "-e (c)at --format={line}:{pattern}:{match}:{1}:{2}";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+1:0:dog:d:
+2:1:cat::c
+3:1:cat::c
+3:0:dog:d:
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn json_multiple_patterns_quiet_and_not_quiet() {
+    let input = "\
+cat dog
+";
+    // With -e there is no pattern before the files.
+    let pattern = "--regexp=cat";
+    let args = "-e dog --json -x";
+    let expected_found_matches = true;
+    let expected_screen_output = r#"{"type":"begin","path":"bogus_file.txt"}
+{"type":"match","path":"bogus_file.txt","line_number":1,"offset":0,"text":"cat dog","matches":[{"text":"cat","start":0,"end":3,"pattern":0,"groups":[]},{"text":"dog","start":4,"end":7,"pattern":1,"groups":[]}]}
+{"type":"end","path":"bogus_file.txt","lines":1,"matches":2}
+"#;
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn format_skip_whole_files_quiet_and_not_quiet() {
+    let input = "\
+the dög dog
+two
+dog
+";
+    let pattern = "(\\w)o";
+    let args = "--format={line}:{column}:{1} -w -k 1 -n 1";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+2:2:w
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn column_and_byte_offset_quiet_and_not_quiet() {
+    let input = "\
+the dög dog
+two
+dog
+";
+    let pattern = "d.g$";
+    let args = "--column --byte-offset";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:1:9:9:the dög dog
+bogus_file.txt:3:1:17:dog
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn column_skip_only_matches_quiet_and_not_quiet() {
+    let input = "\
+the dög dog
+two
+dog
+";
+    let pattern = "d.g";
+    let args = "--column -k 1 -o";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:1:9:dog
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn column_and_byte_offset_whole_files_quiet_and_not_quiet() {
+    let input = "\
+the dög dog
+two
+dog
+";
+    let pattern = "o\ndog";
+    let args = "--column --byte-offset -w";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:2:3:15:
+the dög dog
+two
+dog
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn context_separator_quiet_and_not_quiet() {
+    let input = "\
+one dog
+two
+three
+four
+five dog
+six
+";
+    let pattern = "dog";
+    let args = "-A 1 --context-separator=...";
+    let expected_found_matches = true;
+    let expected_screen_output = "\
+bogus_file.txt:1:one dog
+bogus_file.txt-2-two
+...
+bogus_file.txt:5:five dog
+bogus_file.txt-6-six
+";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn count_quiet_and_not_quiet() {
+    let input = "\
+one dog dog
+two
+three dog
+";
+    let pattern = "dog$";
+    let args = "--count";
+    let expected_found_matches = true;
+    let expected_screen_output = "bogus_file.txt:2\n";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn count_matches_skip_quiet_and_not_quiet() {
+    let input = "\
+one dog dog
+two
+three dog
+";
+    let pattern = "dog";
+    let args = "--count-matches -k 1";
+    let expected_found_matches = true;
+    let expected_screen_output = "bogus_file.txt:1\n";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn count_no_match_quiet_and_not_quiet() {
+    let input = "\
+one dog dog
+two
+three dog
+";
+    let pattern = "dog";
+    let args = "--count-matches -v";
+    let expected_found_matches = true;
+    let expected_screen_output = "bogus_file.txt:1\n";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn count_replacements_quiet_and_not_quiet() {
+    let input = "\
+one dog dog
+two
+three dog
+";
+    let pattern = "dog";
+    let args = "-r cat --count-matches";
+    let expected_found_matches = true;
+    let expected_screen_output = "bogus_file.txt:3\n";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn count_whole_files_quiet_and_not_quiet() {
+    let input = "\
+one dog dog
+two
+three dog
+";
+    let pattern = "dog.*\\n.*\\n.*dog";
+    let args = "--count -w";
+    let expected_found_matches = true;
+    let expected_screen_output = "bogus_file.txt:1\n";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn count_no_match_whole_files_match_at_end_quiet_and_not_quiet() {
+    let input = "\
+one
+two
+";
+    // $ matches after the last line ending, which isn't on a line.
+    let pattern = "one|$";
+    let args = "--count -v -w";
+    let expected_found_matches = true;
+    let expected_screen_output = "bogus_file.txt:1\n";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
+#[test]
+fn count_no_match_whole_files_empty_quiet_and_not_quiet() {
+    let input = "";
+    let pattern = "^";
+    let args = "--count -v -w";
+    let expected_found_matches = true;
+    let expected_screen_output = "";
+    let expected_file_content = &input;
+
+    test(
+        input,
+        pattern,
+        args,
+        expected_found_matches,
+        expected_screen_output,
+        expected_file_content,
+    );
+}
+
 fn test(
     input: &str,
     pattern: &str,
@@ -1689,7 +2447,7 @@ fn really_test(
     args.insert(0, pattern.to_string());
     env::set_var("NED_DEFAULTS", "");
     let options_with_defaults = OptionsWithDefaults::new(make_opts(), &args).unwrap();
-    let parameters = get_parameters(&options_with_defaults).unwrap();
+    let parameters = get_parameters(&options_with_defaults, false).unwrap();
 
     let mut cursor = Cursor::<Vec<u8>>::new(vec![]);
     cursor.write_all(&input.to_string().into_bytes()).unwrap();
@@ -1700,6 +2458,7 @@ fn really_test(
     let found_matches = process_file(
         &mut screen_output,
         &parameters,
+        &mut RunState::new(Box::new(io::empty())),
         &Some("bogus_file.txt".to_string()),
         &mut file,
     )
@@ -20,5 +20,8 @@
 
 mod files;
 mod general;
+mod hyperlinks;
+mod interactive;
 mod matches;
 mod parameters;
+mod rewrite;
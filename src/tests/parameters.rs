@@ -18,7 +18,14 @@
 // 02110-1301, USA.
 //
 
-use crate::parameters::Parameters;
+use crate::links::{Hardlinks, Symlinks};
+use crate::ned_error::NedResult;
+use crate::options_with_defaults::OptionsWithDefaults;
+use crate::opts::make_opts;
+use crate::parameters::{get_parameters, Parameters};
+use crate::patterns::Patterns;
+use crate::preserve::Preserve;
+use std::env;
 
 #[test]
 fn number_normal_range() {
@@ -81,6 +88,33 @@ fn skip_too_many() {
     test_include_match(None, 11, 100, 10, false, false);
 }
 
+#[test]
+fn vimgrep_with_replace() {
+    assert!(parameters(&["dog", "--vimgrep", "-r", "cat", "--stdout"], false).is_err());
+    assert!(parameters(&["dog", "--vimgrep"], false).is_ok());
+}
+
+#[test]
+fn terminal_defaults() {
+    let terminal = parameters(&["dog", "--colors=auto"], true).unwrap();
+    assert!(terminal.heading);
+    assert!(terminal.colors);
+    let not_terminal = parameters(&["dog", "--colors=auto"], false).unwrap();
+    assert!(!not_terminal.heading);
+    assert!(!not_terminal.colors);
+    assert!(!parameters(&["dog", "--no-heading"], true).unwrap().heading);
+}
+
+fn parameters(args: &[&str], isatty: bool) -> NedResult<Parameters> {
+    let args = args
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<String>>();
+    env::set_var("NED_DEFAULTS", "");
+    let options_with_defaults = OptionsWithDefaults::new(make_opts(), &args)?;
+    get_parameters(&options_with_defaults, isatty)
+}
+
 fn test_include_match(
     number: Option<usize>,
     skip: usize,
@@ -91,34 +125,65 @@ fn test_include_match(
 ) {
     let mut parameters = Parameters {
         all: false,
+        backup: None,
         backwards: false,
+        byte_offset: false,
         case_replacements: false,
         colors: false,
+        column: false,
         context_after: 0,
         context_before: 0,
+        context_separator: "--".to_string(),
+        count: false,
+        count_matches: false,
+        diff: None,
         exclude_dirs: vec![],
         excludes: vec![],
         file_names_only: false,
         follow: false,
+        format: None,
+        force_dirty: false,
         globs: vec![],
         group: None,
+        hardlinks: Hardlinks::Break,
+        heading: false,
         help: false,
+        hyperlinks: None,
         ignore_non_utf8: false,
         includes: vec![],
+        interactive: false,
+        journal: None,
+        json: false,
         line_numbers_only: false,
+        literal_replacement: false,
         matches_only: false,
+        max_files: None,
+        max_replacements: None,
         no_file_names: false,
         no_line_numbers: false,
         no_match: false,
+        null: false,
         number,
+        patch: false,
+        patterns: Patterns::default(),
+        preserve: Preserve::default(),
         quiet: false,
         regex: None,
         recursive: false,
         replace: None,
+        require_clean: false,
+        sarif: false,
+        sarif_rules: vec![],
+        show_changes: false,
         skip,
+        stats: false,
         stdin: false,
         stdout: false,
+        symlinks: Symlinks::Follow,
+        transaction: false,
+        undo: None,
         version: false,
+        vimgrep: false,
         whole_files: false,
     };
     assert_eq!(parameters.include_match(index, count), forward_expected);
@@ -0,0 +1,682 @@
+//
+// ned, https://github.com/nevdelap/ned, tests/rewrite.rs
+//
+// Copyright 2016-2021 Nev Delap (nevdelap at gmail)
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation; either version 3, or (at your option)
+// any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+// You should have received a copy of the GNU General Public License
+// along with this program; if not, write to the Free Software
+// Foundation, Inc., 51 Franklin Street - Fifth Floor, Boston, MA
+// 02110-1301, USA.
+//
+
+/// Test rewriting files in place - that replacements reach the disk, and what is left around them.
+use crate::journal::run_id;
+use crate::ned;
+use crate::ned_error::NedError;
+use crate::options_with_defaults::OptionsWithDefaults;
+use crate::opts::make_opts;
+use crate::parameters::{get_parameters, Parameters};
+use crate::preserve::Preserve;
+use crate::rewrite::rewrite_file;
+use crate::run_state::RunState;
+use crate::write_staged_rewrites;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+use time::OffsetDateTime;
+
+#[test]
+fn rewrite_replaces_content_and_leaves_no_temp_file() {
+    let dir = make_test_dir("rewrite_replaces_content");
+    let path = dir.join("file.txt");
+    fs::write(&path, "a much longer original content\n").unwrap();
+
+    rewrite_file(&parameters(&["dog", "dummy"]), &path, &path, b"short\n").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "short\n");
+    assert_eq!(dir_file_names(&dir), vec!["file.txt"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn rewrite_keeps_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = make_test_dir("rewrite_keeps_permissions");
+    let path = dir.join("script.sh");
+    fs::write(&path, "echo dog\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o750)).unwrap();
+
+    rewrite_file(&parameters(&["dog", "dummy"]), &path, &path, b"echo cat\n").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "echo cat\n");
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o750);
+}
+
+#[cfg(unix)]
+#[test]
+fn rewrite_keeps_ownership() {
+    use std::os::unix::fs::{chown, MetadataExt};
+
+    let dir = make_test_dir("rewrite_keeps_ownership");
+    let path = dir.join("file.txt");
+    fs::write(&path, "dog\n").unwrap();
+    // Only root can give the file to someone else, otherwise it is already the user's own.
+    let _ = chown(&path, Some(65534), Some(65534));
+    let metadata = fs::metadata(&path).unwrap();
+
+    run(&["dog", "-r", "cat", path.to_str().unwrap()]);
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "cat\n");
+    let new_metadata = fs::metadata(&path).unwrap();
+    assert_eq!(new_metadata.uid(), metadata.uid());
+    assert_eq!(new_metadata.gid(), metadata.gid());
+}
+
+#[test]
+fn replace_rewrites_only_files_with_matches() {
+    let dir = make_test_dir("replace_rewrites_only_files_with_matches");
+    fs::write(dir.join("dog.txt"), "the dog barked\n").unwrap();
+    fs::write(dir.join("bird.txt"), "the bird sang\n").unwrap();
+
+    let exit_code = run(&["dog", "-r", "cat", dir.to_str().unwrap()]);
+
+    assert_eq!(exit_code, 0);
+    assert_eq!(
+        fs::read_to_string(dir.join("dog.txt")).unwrap(),
+        "the cat barked\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("bird.txt")).unwrap(),
+        "the bird sang\n"
+    );
+    assert_eq!(dir_file_names(&dir), vec!["bird.txt", "dog.txt"]);
+}
+
+#[test]
+fn replace_with_default_backup_suffix() {
+    let dir = make_test_dir("replace_with_default_backup_suffix");
+    fs::write(dir.join("dog.txt"), "the dog barked\n").unwrap();
+    fs::write(dir.join("bird.txt"), "the bird sang\n").unwrap();
+
+    run(&["dog", "-r", "cat", "--backup", dir.to_str().unwrap()]);
+
+    assert_eq!(
+        fs::read_to_string(dir.join("dog.txt")).unwrap(),
+        "the cat barked\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("dog.txt~")).unwrap(),
+        "the dog barked\n"
+    );
+    assert_eq!(
+        dir_file_names(&dir),
+        vec!["bird.txt", "dog.txt", "dog.txt~"]
+    );
+}
+
+#[test]
+fn replace_with_backup_suffix() {
+    let dir = make_test_dir("replace_with_backup_suffix");
+    fs::write(dir.join("dog.txt"), "the dog barked\n").unwrap();
+
+    run(&["dog", "-r", "cat", "--backup=.orig", dir.to_str().unwrap()]);
+
+    assert_eq!(
+        fs::read_to_string(dir.join("dog.txt.orig")).unwrap(),
+        "the dog barked\n"
+    );
+}
+
+#[test]
+fn replace_with_numbered_backups() {
+    let dir = make_test_dir("replace_with_numbered_backups");
+    fs::write(dir.join("dog.txt"), "dog\n").unwrap();
+
+    run(&[
+        "dog",
+        "-r",
+        "cat",
+        "--backup=numbered",
+        dir.to_str().unwrap(),
+    ]);
+    run(&[
+        "cat",
+        "-r",
+        "cow",
+        "--backup=numbered",
+        dir.to_str().unwrap(),
+    ]);
+    run(&[
+        "cow",
+        "-r",
+        "pig",
+        "--backup=numbered",
+        dir.to_str().unwrap(),
+    ]);
+
+    assert_eq!(fs::read_to_string(dir.join("dog.txt")).unwrap(), "pig\n");
+    assert_eq!(
+        fs::read_to_string(dir.join("dog.txt.~1~")).unwrap(),
+        "dog\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("dog.txt.~2~")).unwrap(),
+        "cat\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("dog.txt.~3~")).unwrap(),
+        "cow\n"
+    );
+}
+
+#[test]
+fn undo_restores_journaled_files() {
+    let dir = make_test_dir("undo_restores_journaled_files");
+    let journal_dir = dir.join("journal");
+    let files_dir = dir.join("files");
+    fs::create_dir(&files_dir).unwrap();
+    fs::write(files_dir.join("dog.txt"), "the dog barked\n").unwrap();
+    fs::write(files_dir.join("dogs.txt"), "dogs and dogs\n").unwrap();
+    let journal = format!("--journal={}", journal_dir.to_str().unwrap());
+
+    run(&["dog", "-r", "cat", &journal, files_dir.to_str().unwrap()]);
+
+    assert_eq!(
+        fs::read_to_string(files_dir.join("dog.txt")).unwrap(),
+        "the cat barked\n"
+    );
+    assert_eq!(dir_file_names(&journal_dir).len(), 1);
+
+    let exit_code = run(&["--undo", &journal]);
+
+    assert_eq!(exit_code, 0);
+    assert_eq!(
+        fs::read_to_string(files_dir.join("dog.txt")).unwrap(),
+        "the dog barked\n"
+    );
+    assert_eq!(
+        fs::read_to_string(files_dir.join("dogs.txt")).unwrap(),
+        "dogs and dogs\n"
+    );
+    assert!(dir_file_names(&journal_dir).is_empty());
+}
+
+#[test]
+fn undo_does_not_restore_changed_files() {
+    let dir = make_test_dir("undo_does_not_restore_changed_files");
+    let journal_dir = dir.join("journal");
+    let files_dir = dir.join("files");
+    fs::create_dir(&files_dir).unwrap();
+    fs::write(files_dir.join("dog.txt"), "the dog barked\n").unwrap();
+    fs::write(files_dir.join("dogs.txt"), "dogs and dogs\n").unwrap();
+    let journal = format!("--journal={}", journal_dir.to_str().unwrap());
+
+    run(&["dog", "-r", "cat", &journal, files_dir.to_str().unwrap()]);
+    fs::write(files_dir.join("dogs.txt"), "cats and birds\n").unwrap();
+    let runs = dir_file_names(&journal_dir);
+    let undo = format!("--undo={}", runs[0]);
+
+    let exit_code = run(&[&undo, &journal]);
+
+    assert_eq!(exit_code, 1);
+    assert_eq!(
+        fs::read_to_string(files_dir.join("dog.txt")).unwrap(),
+        "the dog barked\n"
+    );
+    assert_eq!(
+        fs::read_to_string(files_dir.join("dogs.txt")).unwrap(),
+        "cats and birds\n"
+    );
+    // The changed file's entry stays in the journal.
+    assert_eq!(dir_file_names(&journal_dir.join(&runs[0])).len(), 2);
+}
+
+#[test]
+fn undo_with_no_runs() {
+    let dir = make_test_dir("undo_with_no_runs");
+    let args = vec![
+        "--undo".to_string(),
+        format!("--journal={}", dir.join("journal").to_str().unwrap()),
+    ];
+    let mut screen_output: Vec<u8> = vec![];
+    assert!(matches!(
+        ned(&mut screen_output, &args, false),
+        Err(NedError::RunError(_))
+    ));
+}
+
+#[test]
+fn run_ids_sort_by_when_runs_started() {
+    let now = OffsetDateTime::now_utc();
+    assert!(run_id(now, 999) < run_id(now, 1000));
+    assert!(run_id(now, 4242) < run_id(now + Duration::from_nanos(1), 1));
+    assert!(run_id(now, 4242) < run_id(now + Duration::from_secs(1), 1));
+}
+
+#[test]
+fn undo_after_failed_rewrite() {
+    let dir = make_test_dir("undo_after_failed_rewrite");
+    let journal_dir = dir.join("journal");
+    let files_dir = dir.join("files");
+    fs::create_dir(&files_dir).unwrap();
+    fs::write(files_dir.join("dog.txt"), "the dog barked\n").unwrap();
+    fs::write(files_dir.join("dogs.txt"), "dogs and dogs\n").unwrap();
+    block_temp_files(&files_dir.join("dogs.txt"));
+    let journal = format!("--journal={}", journal_dir.to_str().unwrap());
+
+    run(&["dog", "-r", "cat", &journal, files_dir.to_str().unwrap()]);
+    assert_eq!(
+        fs::read_to_string(files_dir.join("dogs.txt")).unwrap(),
+        "dogs and dogs\n"
+    );
+
+    // Only the file that was replaced is in the journal, so it is all undone.
+    let exit_code = run(&["--undo", &journal]);
+
+    assert_eq!(exit_code, 0);
+    assert_eq!(
+        fs::read_to_string(files_dir.join("dog.txt")).unwrap(),
+        "the dog barked\n"
+    );
+    assert!(dir_file_names(&journal_dir).is_empty());
+}
+
+#[test]
+fn replace_preserving_timestamps() {
+    let dir = make_test_dir("replace_preserving_timestamps");
+    let path = dir.join("dog.txt");
+    fs::write(&path, "the dog barked\n").unwrap();
+    let long_ago = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_times(
+            fs::FileTimes::new()
+                .set_accessed(long_ago)
+                .set_modified(long_ago),
+        )
+        .unwrap();
+
+    run(&[
+        "dog",
+        "-r",
+        "cat",
+        "--preserve=timestamps",
+        dir.to_str().unwrap(),
+    ]);
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "the cat barked\n");
+    assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), long_ago);
+
+    run(&["cat", "-r", "cow", dir.to_str().unwrap()]);
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "the cow barked\n");
+    assert_ne!(fs::metadata(&path).unwrap().modified().unwrap(), long_ago);
+}
+
+#[test]
+fn preserve_attributes() {
+    let all = Preserve {
+        ownership: true,
+        timestamps: true,
+    };
+    assert_eq!("".parse::<Preserve>().unwrap(), all);
+    assert_eq!("all".parse::<Preserve>().unwrap(), all);
+    assert_eq!(
+        "mode,ownership,timestamps".parse::<Preserve>().unwrap(),
+        all
+    );
+    assert_eq!(
+        "timestamps".parse::<Preserve>().unwrap(),
+        Preserve {
+            ownership: false,
+            timestamps: true,
+        }
+    );
+    assert!("mode,owner".parse::<Preserve>().is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn replace_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let dir = make_test_dir("replace_symlinks");
+    let target = dir.join("target.txt");
+    let link = dir.join("link.txt");
+    symlink(&target, &link).unwrap();
+    let link = link.to_str().unwrap();
+
+    fs::write(&target, "dog\n").unwrap();
+    run(&["dog", "-r", "cat", "--follow", "--symlinks=skip", link]);
+    assert_eq!(fs::read_to_string(&target).unwrap(), "dog\n");
+
+    run(&["dog", "-r", "cat", "--follow", "--symlinks=follow", link]);
+    assert_eq!(fs::read_to_string(&target).unwrap(), "cat\n");
+    assert!(fs::symlink_metadata(link).unwrap().file_type().is_symlink());
+
+    run(&[
+        "cat",
+        "-r",
+        "cow",
+        "--follow",
+        "--symlinks=replace-link",
+        link,
+    ]);
+    assert_eq!(fs::read_to_string(&target).unwrap(), "cat\n");
+    assert_eq!(fs::read_to_string(link).unwrap(), "cow\n");
+    assert!(fs::symlink_metadata(link).unwrap().file_type().is_file());
+}
+
+#[cfg(unix)]
+#[test]
+fn replace_in_symlinked_dir() {
+    use std::os::unix::fs::symlink;
+
+    let dir = make_test_dir("replace_in_symlinked_dir");
+    fs::create_dir(dir.join("vendor")).unwrap();
+    fs::write(dir.join("vendor").join("dog.txt"), "dog\n").unwrap();
+    fs::create_dir(dir.join("project")).unwrap();
+    symlink(dir.join("vendor"), dir.join("project").join("vendor")).unwrap();
+    let project = dir.join("project");
+
+    run(&[
+        "dog",
+        "-r",
+        "cat",
+        "-R",
+        "--follow",
+        "--symlinks=skip",
+        project.to_str().unwrap(),
+    ]);
+    assert_eq!(
+        fs::read_to_string(dir.join("vendor").join("dog.txt")).unwrap(),
+        "dog\n"
+    );
+
+    run(&[
+        "dog",
+        "-r",
+        "cat",
+        "-R",
+        "--follow",
+        project.to_str().unwrap(),
+    ]);
+    assert_eq!(
+        fs::read_to_string(dir.join("vendor").join("dog.txt")).unwrap(),
+        "cat\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn replace_named_in_symlinked_dir() {
+    use std::os::unix::fs::symlink;
+
+    let dir = make_test_dir("replace_named_in_symlinked_dir");
+    fs::create_dir(dir.join("vendor")).unwrap();
+    fs::create_dir(dir.join("project")).unwrap();
+    symlink(dir.join("vendor"), dir.join("project").join("vendor")).unwrap();
+    let file = dir.join("project").join("vendor").join("dog.txt");
+    // The same file named relative to the current directory.
+    let current_dir = env::current_dir().unwrap();
+    let relative_file = current_dir
+        .ancestors()
+        .skip(1)
+        .map(|_| Path::new(".."))
+        .collect::<PathBuf>()
+        .join(file.strip_prefix("/").unwrap());
+
+    // Directories that were named rather than walked into aren't symlinked directories,
+    // however the file is named.
+    for file_arg in &[&file, &relative_file] {
+        fs::write(dir.join("vendor").join("dog.txt"), "dog\n").unwrap();
+        run(&[
+            "dog",
+            "-r",
+            "cat",
+            "--symlinks=skip",
+            file_arg.to_str().unwrap(),
+        ]);
+        assert_eq!(
+            fs::read_to_string(dir.join("vendor").join("dog.txt")).unwrap(),
+            "cat\n"
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn replace_hardlinks() {
+    let dir = make_test_dir("replace_hardlinks");
+    let file = dir.join("file.txt");
+    let link = dir.join("link.txt");
+    fs::write(&file, "dog\n").unwrap();
+    fs::hard_link(&file, &link).unwrap();
+    let file_arg = file.to_str().unwrap();
+
+    run(&["dog", "-r", "cat", "--hardlinks=skip", file_arg]);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "dog\n");
+
+    run(&["dog", "-r", "cat", "--hardlinks=edit", file_arg]);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "cat\n");
+    assert_eq!(fs::read_to_string(&link).unwrap(), "cat\n");
+
+    run(&["cat", "-r", "cow", "--hardlinks=break", file_arg]);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "cow\n");
+    assert_eq!(fs::read_to_string(&link).unwrap(), "cat\n");
+}
+
+#[test]
+fn replace_with_max_replacements_and_max_files() {
+    let dir = make_test_dir("replace_with_max_replacements_and_max_files");
+    fs::write(dir.join("one.txt"), "dog dog\n").unwrap();
+    fs::write(dir.join("two.txt"), "dog\n").unwrap();
+    let dir_arg = dir.to_str().unwrap();
+
+    for limit in &["--max-replacements=2", "--max-files=1"] {
+        let args = ["dog", "-r", "cat", limit, dir_arg]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<String>>();
+        let mut screen_output: Vec<u8> = vec![];
+        assert!(matches!(
+            ned(&mut screen_output, &args, false),
+            Err(NedError::RunError(_))
+        ));
+        assert_eq!(
+            fs::read_to_string(dir.join("one.txt")).unwrap(),
+            "dog dog\n"
+        );
+        assert_eq!(fs::read_to_string(dir.join("two.txt")).unwrap(), "dog\n");
+    }
+
+    let exit_code = run(&[
+        "dog",
+        "-r",
+        "cat",
+        "--max-replacements=3",
+        "--max-files=2",
+        dir_arg,
+    ]);
+
+    assert_eq!(exit_code, 0);
+    assert_eq!(
+        fs::read_to_string(dir.join("one.txt")).unwrap(),
+        "cat cat\n"
+    );
+    assert_eq!(fs::read_to_string(dir.join("two.txt")).unwrap(), "cat\n");
+}
+
+#[test]
+fn replace_in_transaction() {
+    let dir = make_test_dir("replace_in_transaction");
+    fs::write(dir.join("one.txt"), "dog\n").unwrap();
+    fs::write(dir.join("two.txt"), b"dog \xff\n").unwrap();
+    let dir_arg = dir.to_str().unwrap();
+
+    let args = ["dog", "-r", "cat", "--transaction", dir_arg]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<String>>();
+    let mut screen_output: Vec<u8> = vec![];
+    assert!(matches!(
+        ned(&mut screen_output, &args, false),
+        Err(NedError::RunError(_))
+    ));
+    assert_eq!(fs::read_to_string(dir.join("one.txt")).unwrap(), "dog\n");
+
+    run(&["dog", "-r", "cat", dir_arg]);
+    assert_eq!(fs::read_to_string(dir.join("one.txt")).unwrap(), "cat\n");
+}
+
+#[test]
+fn transaction_with_file_that_cannot_be_read() {
+    let dir = make_test_dir("transaction_with_file_that_cannot_be_read");
+    fs::write(dir.join("one.txt"), "dog\n").unwrap();
+    let mut run_state = RunState::new(Box::new(io::empty()));
+    run_state.rewrites = vec![
+        (dir.join("one.txt"), dir.clone(), b"cat\n".to_vec()),
+        // Removed since it was searched.
+        (dir.join("two.txt"), dir.clone(), b"cat\n".to_vec()),
+    ];
+
+    let result = write_staged_rewrites(
+        &parameters(&["dog", "-r", "cat", "--transaction"]),
+        &mut run_state,
+    );
+
+    assert!(matches!(result, Err(NedError::RunError(_))));
+    assert_eq!(fs::read_to_string(dir.join("one.txt")).unwrap(), "dog\n");
+}
+
+#[test]
+fn replace_files_with_uncommitted_changes() {
+    let dir = make_test_dir("replace_files_with_uncommitted_changes");
+    fs::write(dir.join("clean.txt"), "dog\n").unwrap();
+    fs::write(dir.join("dirty.txt"), "dog\n").unwrap();
+    if !git(&dir, &["init", "-q"]) {
+        // Git isn't installed.
+        return;
+    }
+    assert!(git(&dir, &["add", "."]));
+    assert!(git(
+        &dir,
+        &[
+            "-c",
+            "user.name=ned",
+            "-c",
+            "user.email=ned@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "Dogs."
+        ]
+    ));
+    fs::write(dir.join("dirty.txt"), "dog dog\n").unwrap();
+    let dir_arg = dir.to_str().unwrap();
+
+    let args = ["dog", "-r", "cat", "--require-clean", dir_arg]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<String>>();
+    let mut screen_output: Vec<u8> = vec![];
+    assert!(matches!(
+        ned(&mut screen_output, &args, false),
+        Err(NedError::RunError(_))
+    ));
+    assert_eq!(fs::read_to_string(dir.join("clean.txt")).unwrap(), "dog\n");
+
+    run(&["dog", "-r", "cat", dir_arg]);
+    assert_eq!(fs::read_to_string(dir.join("clean.txt")).unwrap(), "cat\n");
+    assert_eq!(
+        fs::read_to_string(dir.join("dirty.txt")).unwrap(),
+        "dog dog\n"
+    );
+
+    run(&["dog", "-r", "cat", "--force-dirty", dir_arg]);
+    assert_eq!(
+        fs::read_to_string(dir.join("dirty.txt")).unwrap(),
+        "cat cat\n"
+    );
+}
+
+fn parameters(args: &[&str]) -> Parameters {
+    let args = args
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<String>>();
+    env::set_var("NED_DEFAULTS", "");
+    let options_with_defaults = OptionsWithDefaults::new(make_opts(), &args).unwrap();
+    get_parameters(&options_with_defaults, false).unwrap()
+}
+
+fn run(args: &[&str]) -> i32 {
+    let args = args
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<String>>();
+    env::set_var("NED_DEFAULTS", "");
+    let mut screen_output: Vec<u8> = vec![];
+    ned(&mut screen_output, &args, false).unwrap()
+}
+
+/// Make an empty directory, unique to the test, to write files into.
+fn make_test_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("ned_test_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Make writing the file fail by taking all of the temporary file names that ned would write
+/// it with, which works even for root, unlike taking away permissions.
+fn block_temp_files(path: &Path) {
+    let file_name = path.file_name().unwrap().to_string_lossy();
+    for attempt in 0..=100 {
+        fs::write(
+            path.with_file_name(format!(
+                ".{}.ned-{}-{}.tmp",
+                file_name,
+                std::process::id(),
+                attempt
+            )),
+            "",
+        )
+        .unwrap();
+    }
+}
+
+fn dir_file_names(dir: &PathBuf) -> Vec<String> {
+    let mut file_names = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect::<Vec<String>>();
+    file_names.sort();
+    file_names
+}
+
+fn git(dir: &PathBuf, args: &[&str]) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}